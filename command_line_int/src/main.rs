@@ -1,5 +1,6 @@
 mod builtin;
 
+use std::rc::Rc;
 pub use nylisp_eval;
 
 fn read_line() -> String {
@@ -10,6 +11,9 @@ fn read_line() -> String {
 
 fn main() {
     let mut global_env = nylisp_eval::environment::environment::builtin_env();
+    // 📥 looks for name.nylisp next to where the repl was started
+    let here = std::env::current_dir().unwrap();
+    global_env.set_module_loader(Rc::new(nylisp_eval::module::module::FileLoader::new(here)));
     println!("-o welcome to nylisp repl");
     println!("-! ctrl+c to exit");
     loop {
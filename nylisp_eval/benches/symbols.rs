@@ -0,0 +1,53 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use nylisp_eval::ast::ast::{NylispExpression, Symbol};
+use std::collections::HashMap;
+
+const VARIABLES: usize = 200;
+
+fn names() -> Vec<String> {
+    (0..VARIABLES).map(|i| format!("🍙{}🍡", i)).collect()
+}
+
+// define a lot of emoji variables, then sum all of them inside nested scopes
+fn symbol_heavy_program() -> String {
+    let mut program = String::new();
+    for name in names() {
+        program.push_str(&format!("💖🌹 {} 1💔 ", name));
+    }
+    let mut body = format!("💖+ {}💔", names().join(" "));
+    for depth in 0..8 {
+        body = format!("💖🍙 💖💖depth{} {}💔💔 {}💔", depth, depth, body);
+    }
+    program.push_str(&body);
+    program
+}
+
+fn bench_program(c: &mut Criterion) {
+    let tokens = nylisp_eval::tokenize_nylisp(symbol_heavy_program());
+    let ast: Vec<NylispExpression> = nylisp_eval::parse_nylisps(tokens).into_iter().map(|e| e.unwrap()).collect();
+    c.bench_function("evaluate symbol heavy program", |b| {
+        b.iter(|| {
+            let mut env = nylisp_eval::environment::environment::builtin_env();
+            black_box(nylisp_eval::evaluate_nylisp(ast.clone(), &mut env))
+        })
+    });
+}
+
+// the same lookups the environment does, keyed by strings and by interned symbols
+fn bench_lookup(c: &mut Criterion) {
+    let names = names();
+    let by_string: HashMap<String, f64> = names.iter().map(|n| (n.clone(), 1.0)).collect();
+    let keys: Vec<String> = names.clone();
+    c.bench_function("lookup string keys", |b| {
+        b.iter(|| keys.iter().map(|k| by_string[k]).sum::<f64>())
+    });
+
+    let by_symbol: HashMap<Symbol, f64> = names.iter().map(|n| (Symbol::intern(n), 1.0)).collect();
+    let keys: Vec<Symbol> = names.iter().map(|n| Symbol::intern(n)).collect();
+    c.bench_function("lookup interned symbols", |b| {
+        b.iter(|| keys.iter().map(|k| by_symbol[k]).sum::<f64>())
+    });
+}
+
+criterion_group!(benches, bench_program, bench_lookup);
+criterion_main!(benches);
@@ -1,26 +1,226 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
-use std::path::Display;
 use std::rc::Rc;
+use std::sync::{Mutex, OnceLock};
+use crate::evaluation;
+use crate::module;
 use crate::tokenizer;
 
+// interned symbol, equality and hashing only look at the id
+#[derive(Clone, Copy)]
+pub struct Symbol {
+    id: u32,
+    name: &'static str,
+}
+
+// names are leaked once and live for the whole program, like in most lisp symbol tables
+struct Interner {
+    ids: HashMap<&'static str, u32>,
+}
+
+fn interner() -> &'static Mutex<Interner> {
+    static INTERNER: OnceLock<Mutex<Interner>> = OnceLock::new();
+    INTERNER.get_or_init(|| Mutex::new(Interner { ids: HashMap::new() }))
+}
+
+impl Symbol {
+    pub fn intern(name: &str) -> Symbol {
+        let mut interner = interner().lock().unwrap();
+        if let Some((name, id)) = interner.ids.get_key_value(name) {
+            return Symbol { id: *id, name };
+        }
+        let id = interner.ids.len() as u32;
+        let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+        interner.ids.insert(name, id);
+        Symbol { id, name }
+    }
+
+    // the symbol for name if something already interned it, probing never adds to the table
+    pub fn lookup(name: &str) -> Option<Symbol> {
+        let interner = interner().lock().unwrap();
+        interner.ids.get_key_value(name).map(|(name, id)| Symbol { id: *id, name })
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.name
+    }
+
+    // a symbol nobody has used yet, for macros that need names of their own
+    pub fn gensym(prefix: &str) -> Symbol {
+        let mut interner = interner().lock().unwrap();
+        let mut count = interner.ids.len();
+        loop {
+            let candidate = format!("{}{}", prefix, count);
+            if !interner.ids.contains_key(candidate.as_str()) {
+                let id = interner.ids.len() as u32;
+                let name: &'static str = Box::leak(candidate.into_boxed_str());
+                interner.ids.insert(name, id);
+                return Symbol { id, name };
+            }
+            count += 1;
+        }
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Symbol {
+        Symbol::intern(name)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(name: String) -> Symbol {
+        Symbol::intern(name.as_str())
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Symbol {}
+
+impl std::hash::Hash for Symbol {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl std::fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self.name)
+    }
+}
+
+impl std::fmt::Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
 // exp
-#[derive(Clone, Debug, PartialOrd, PartialEq)]
+#[derive(Clone, Debug)]
 pub enum NylispExpression {
     Quote(Rc<NylispExpression>),
-    Symbol(String),
+    Symbol(Symbol),
+    // self-evaluating tag, shares the symbol table but never equals a symbol
+    Keyword(Symbol),
     Number(f64),
     Boolean(bool),
     String(String),
+    // a single grapheme cluster, so 👍🏽 is one character
+    Char(String),
     List(Vec<NylispExpression>),
+    // error object, what 🎣 handlers receive
+    Error {
+        kind: Symbol,
+        message: String,
+        payload: Rc<NylispExpression>,
+    },
+    // unit value for "nothing", distinct from 👎 and the empty list
+    Nil,
+    // cons cell, car and cdr are shared so 💭 never copies the rest of the list
+    Pair(Rc<NylispExpression>, Rc<NylispExpression>),
+    // what 💭 gives for a list, its elements from the offset on, sharing the list instead of copying it
+    Tail(ListTail),
     Function(fn(Vec<NylispExpression>) -> Result<NylispExpression, NylispError>),
     Closure {
         args: Rc<NylispExpression>,
         body: Rc<NylispExpression>,
+        // scope the closure was created in
+        env: Environment,
+        // set by 🍳, used for printing and error traces
+        name: Option<Symbol>,
+        doc: Option<String>,
     },
     ScopedLet {
         variables: Rc<NylispExpression>,
         body: Rc<NylispExpression>,
     },
+    // captured by 🔖, calling it with a value makes the 🔖 form return that value again
+    Continuation(Captured),
+    // captured by 🪝 up to the nearest 🚧, calling it runs those frames and returns what the 🚧 would have
+    DelimitedContinuation(Captured),
+    // made by 🧙, the closure gets the unevaluated arguments and returns the code to run instead
+    Macro(Rc<NylispExpression>),
+    // made by 🧬, uses are rewritten by the expander before they are evaluated
+    SyntaxRules {
+        name: Symbol,
+        literals: Rc<Vec<Symbol>>,
+        rules: Rc<Vec<(NylispExpression, NylispExpression)>>,
+    },
+    // made by 💤, ⏰ evaluates it the first time and remembers the value
+    Promise(Rc<RefCell<Lazy>>),
+    // made by 🐣, 🎬 runs it until it 🫳 yields a value or returns
+    Coroutine(Rc<RefCell<CoroutineState>>),
+    // instance of a 📇 record type, fields in the order they were declared
+    Record {
+        name: Symbol,
+        fields: Rc<Vec<(Symbol, NylispExpression)>>,
+    },
+}
+
+// frames captured by 🔖, 🪝 or a yielding 🐣, only the evaluator can look inside
+#[derive(Clone, Debug, PartialEq)]
+pub struct Captured(pub(crate) evaluation::frame::Continuation);
+
+// the shared list and offset behind a 💭 result, unroll or proper_list gives its elements
+#[derive(Clone, Debug)]
+pub struct ListTail {
+    list: Rc<Vec<NylispExpression>>,
+    offset: usize,
+}
+
+impl ListTail {
+    pub(crate) fn first(&self) -> Option<&NylispExpression> {
+        self.list.get(self.offset)
+    }
+
+    pub(crate) fn rest(&self) -> NylispExpression {
+        NylispExpression::tail(self.list.clone(), self.offset + 1)
+    }
+
+    fn items(&self) -> &[NylispExpression] {
+        &self.list[self.offset..]
+    }
+}
+
+impl PartialEq for ListTail {
+    fn eq(&self, other: &Self) -> bool {
+        (Rc::ptr_eq(&self.list, &other.list) && self.offset == other.offset) || self.items() == other.items()
+    }
+}
+
+// state of a 💤 promise
+#[derive(Clone, Debug)]
+pub enum Lazy {
+    Delayed {
+        exp: NylispExpression,
+        env: Environment,
+    },
+    Forced(NylispExpression),
+}
+
+// state of a 🐣 coroutine, Fresh holds the function it runs and Suspended the frames left when it yielded
+#[derive(Clone, Debug)]
+pub enum CoroutineState {
+    Fresh(NylispExpression),
+    Suspended(Captured),
+    Running,
+    Dead,
+}
+
+impl CoroutineState {
+    // what 🚥 reports, a coroutine that hasn't started yet is suspended at its beginning
+    pub fn status(&self) -> &'static str {
+        match self {
+            CoroutineState::Fresh(_) | CoroutineState::Suspended(_) => "suspended",
+            CoroutineState::Running => "running",
+            CoroutineState::Dead => "dead",
+        }
+    }
 }
 
 impl std::fmt::Display for NylispExpression {
@@ -28,38 +228,299 @@ impl std::fmt::Display for NylispExpression {
         match self {
             NylispExpression::Quote(exp) => write!(f, "'{}", *exp),
             NylispExpression::Symbol(sym) => write!(f, "{}", sym),
+            NylispExpression::Keyword(key) => write!(f, "{}{}", tokenizer::tokenizer::KEYWORD, key),
             NylispExpression::Number(num) => write!(f, "{}", num),
             NylispExpression::Boolean(b) => write!(f, "{}", b),
+            NylispExpression::Nil => write!(f, "{}", tokenizer::tokenizer::NIL),
             NylispExpression::String(s) => write!(f, "{}", s),
-            NylispExpression::List(list) => {
+            NylispExpression::Char(c) => {
+                match tokenizer::tokenizer::CHAR_NAMES.iter().find(|(_, ch)| ch == c) {
+                    Some((name, _)) => write!(f, "{}{}", tokenizer::tokenizer::CHAR, name),
+                    None => write!(f, "{}{}", tokenizer::tokenizer::CHAR, c),
+                }
+            }
+            NylispExpression::List(_) | NylispExpression::Pair(_, _) | NylispExpression::Tail(_) => {
+                let (items, tail) = self.unroll().unwrap();
                 let mut s = String::new();
                 s.push_str(tokenizer::tokenizer::LPAREN.to_string().as_str());
-                for exp in list {
+                for exp in items {
                     s.push_str(&format!("{} ", exp));
                 }
+                if let Some(tail) = tail {
+                    s.push_str(&format!("{} {} ", tokenizer::tokenizer::DOT, tail));
+                }
                 s.push_str(tokenizer::tokenizer::RPAREN.to_string().as_str());
                 write!(f, "{}", s)
             }
             NylispExpression::Function(_) => write!(f, "<function>"),
+            NylispExpression::Error { kind, message, .. } => write!(f, "<error {}{} {}>", tokenizer::tokenizer::KEYWORD, kind, message),
+            NylispExpression::Closure { name: Some(name), .. } => write!(f, "<closure {}>", name),
             NylispExpression::Closure { .. } => write!(f, "<closure>"),
             NylispExpression::ScopedLet { .. } => write!(f, "<scoped-let>"),
+            NylispExpression::Continuation(_) => write!(f, "<continuation>"),
+            NylispExpression::DelimitedContinuation(_) => write!(f, "<delimited continuation>"),
+            NylispExpression::SyntaxRules { name, .. } => write!(f, "<syntax {}>", name),
+            NylispExpression::Promise(_) => write!(f, "<promise>"),
+            NylispExpression::Coroutine(state) => write!(f, "<coroutine {}>", state.borrow().status()),
+            NylispExpression::Record { name, fields } => {
+                write!(f, "#<{}", name)?;
+                for (field, value) in fields.iter() {
+                    write!(f, " {}={}", field, value)?;
+                }
+                write!(f, ">")
+            }
+            NylispExpression::Macro(transformer) => match &**transformer {
+                NylispExpression::Closure { name: Some(name), .. } => write!(f, "<macro {}>", name),
+                _ => write!(f, "<macro>"),
+            },
         }
     }
 }
 
-// environment
-#[derive(Clone, Debug, PartialEq)]
-pub struct Environment<'a> {
-    pub(crate) data: HashMap<String, NylispExpression>,
-    pub(crate) _virtual: Option<&'a Environment<'a>>
+impl NylispExpression {
+    // walk a list or a chain of pairs, returning its elements and the improper tail if there is one
+    pub fn unroll(&self) -> Option<(Vec<NylispExpression>, Option<NylispExpression>)> {
+        let mut items: Vec<NylispExpression> = Vec::new();
+        let mut cur: &NylispExpression = self;
+        loop {
+            match cur {
+                NylispExpression::Pair(car, cdr) => {
+                    items.push((**car).clone());
+                    cur = cdr;
+                }
+                NylispExpression::List(list) => {
+                    items.extend(list.iter().cloned());
+                    return Some((items, None));
+                }
+                NylispExpression::Tail(tail) => {
+                    items.extend(tail.items().iter().cloned());
+                    return Some((items, None));
+                }
+                _ if items.is_empty() => return None,
+                _ => return Some((items, Some(cur.clone()))),
+            }
+        }
+    }
+
+    // 👎 and 🫥 are falsy, everything else (including 0 and the empty list) is truthy
+    pub fn is_truthy(&self) -> bool {
+        !matches!(self, NylispExpression::Boolean(false) | NylispExpression::Nil)
+    }
+
+    // the rest of list from offset on, the empty list once nothing is left so 🈳 still sees it
+    pub(crate) fn tail(list: Rc<Vec<NylispExpression>>, offset: usize) -> NylispExpression {
+        if offset < list.len() {
+            NylispExpression::Tail(ListTail { list, offset })
+        } else {
+            NylispExpression::List(vec![])
+        }
+    }
+
+    // elements of a proper list, None for improper lists and non-lists
+    pub fn proper_list(&self) -> Option<Vec<NylispExpression>> {
+        match self.unroll() {
+            Some((items, None)) => Some(items),
+            _ => None,
+        }
+    }
 }
 
+// lists built with 🍡 compare equal to the same list written literally
+impl PartialEq for NylispExpression {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (NylispExpression::Quote(a), NylispExpression::Quote(b)) => a == b,
+            (NylispExpression::Symbol(a), NylispExpression::Symbol(b)) => a == b,
+            (NylispExpression::Keyword(a), NylispExpression::Keyword(b)) => a == b,
+            (NylispExpression::Number(a), NylispExpression::Number(b)) => a == b,
+            (NylispExpression::Boolean(a), NylispExpression::Boolean(b)) => a == b,
+            (NylispExpression::String(a), NylispExpression::String(b)) => a == b,
+            (NylispExpression::Char(a), NylispExpression::Char(b)) => a == b,
+            (NylispExpression::Nil, NylispExpression::Nil) => true,
+            (
+                NylispExpression::Error { kind: a_kind, message: a_message, payload: a_payload },
+                NylispExpression::Error { kind: b_kind, message: b_message, payload: b_payload },
+            ) => a_kind == b_kind && a_message == b_message && a_payload == b_payload,
+            (NylispExpression::List(a), NylispExpression::List(b)) => a == b,
+            (NylispExpression::Tail(a), NylispExpression::Tail(b)) => a == b,
+            (
+                NylispExpression::List(_) | NylispExpression::Pair(_, _) | NylispExpression::Tail(_),
+                NylispExpression::List(_) | NylispExpression::Pair(_, _) | NylispExpression::Tail(_),
+            ) => self.unroll() == other.unroll(),
+            (NylispExpression::Function(a), NylispExpression::Function(b)) => std::ptr::fn_addr_eq(*a, *b),
+            (
+                NylispExpression::Closure { args: a_args, body: a_body, env: a_env, name: a_name, .. },
+                NylispExpression::Closure { args: b_args, body: b_body, env: b_env, name: b_name, .. },
+            ) => a_args == b_args && a_body == b_body && a_env == b_env && a_name == b_name,
+            (
+                NylispExpression::ScopedLet { variables: a_vars, body: a_body },
+                NylispExpression::ScopedLet { variables: b_vars, body: b_body },
+            ) => a_vars == b_vars && a_body == b_body,
+            (NylispExpression::Continuation(a), NylispExpression::Continuation(b)) => a == b,
+            (NylispExpression::DelimitedContinuation(a), NylispExpression::DelimitedContinuation(b)) => a == b,
+            (NylispExpression::Macro(a), NylispExpression::Macro(b)) => a == b,
+            (
+                NylispExpression::SyntaxRules { name: a_name, literals: a_literals, rules: a_rules },
+                NylispExpression::SyntaxRules { name: b_name, literals: b_literals, rules: b_rules },
+            ) => a_name == b_name && a_literals == b_literals && a_rules == b_rules,
+            (NylispExpression::Promise(a), NylispExpression::Promise(b)) => Rc::ptr_eq(a, b),
+            (NylispExpression::Coroutine(a), NylispExpression::Coroutine(b)) => Rc::ptr_eq(a, b),
+            (
+                NylispExpression::Record { name: a_name, fields: a_fields },
+                NylispExpression::Record { name: b_name, fields: b_fields },
+            ) => a_name == b_name && a_fields == b_fields,
+            _ => false,
+        }
+    }
+}
+
+// plain data orders like it did before pairs had to be compared by hand, anything else only compares equal to itself
+impl PartialOrd for NylispExpression {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        match (self, other) {
+            (NylispExpression::Symbol(a), NylispExpression::Symbol(b)) => a.as_str().partial_cmp(b.as_str()),
+            (NylispExpression::Keyword(a), NylispExpression::Keyword(b)) => a.as_str().partial_cmp(b.as_str()),
+            (NylispExpression::Number(a), NylispExpression::Number(b)) => a.partial_cmp(b),
+            (NylispExpression::Boolean(a), NylispExpression::Boolean(b)) => a.partial_cmp(b),
+            (NylispExpression::String(a), NylispExpression::String(b)) => a.partial_cmp(b),
+            (NylispExpression::Char(a), NylispExpression::Char(b)) => a.partial_cmp(b),
+            (NylispExpression::Quote(a), NylispExpression::Quote(b)) => a.partial_cmp(b),
+            (
+                NylispExpression::List(_) | NylispExpression::Pair(_, _) | NylispExpression::Tail(_),
+                NylispExpression::List(_) | NylispExpression::Pair(_, _) | NylispExpression::Tail(_),
+            ) => self.unroll().partial_cmp(&other.unroll()),
+            _ if self == other => Some(std::cmp::Ordering::Equal),
+            _ => None,
+        }
+    }
+}
+
+thread_local! {
+    // stands in for the frame a closure is stored in, see Environment::store
+    static OWN_FRAME: Rc<RefCell<HashMap<Symbol, NylispExpression>>> = Rc::default();
+}
+
+// environment, frames are shared so closures and 📝 see the same bindings
+#[derive(Clone)]
+pub struct Environment {
+    pub(crate) data: Rc<RefCell<HashMap<Symbol, NylispExpression>>>,
+    pub(crate) _virtual: Option<Rc<Environment>>,
+    // modules loaded by 📥, shared by every frame and module of one program
+    pub(crate) modules: Rc<RefCell<module::module::Modules>>,
+    // whether 📐 contracts are checked, shared by every frame but not by modules
+    pub(crate) contracts: Rc<std::cell::Cell<bool>>,
+}
+
+impl Environment {
+    pub fn new(data: HashMap<Symbol, NylispExpression>) -> Environment {
+        Environment::sharing(data, Rc::default())
+    }
+
+    pub(crate) fn sharing(data: HashMap<Symbol, NylispExpression>, modules: Rc<RefCell<module::module::Modules>>) -> Environment {
+        Environment {
+            data: Rc::new(RefCell::new(data)),
+            _virtual: None,
+            modules,
+            contracts: Rc::new(std::cell::Cell::new(true)),
+        }
+    }
+
+    // new frame on top of this one
+    pub fn extend(&self, data: HashMap<Symbol, NylispExpression>) -> Environment {
+        Environment {
+            data: Rc::new(RefCell::new(data)),
+            _virtual: Some(Rc::new(self.clone())),
+            modules: self.modules.clone(),
+            contracts: self.contracts.clone(),
+        }
+    }
+
+    // check 📐 contracts of closures called in this environment, on by default. 💖🦺 👎💔 does the same from nylisp
+    pub fn set_contract_checking(&self, on: bool) {
+        self.contracts.set(on);
+    }
+
+    pub fn contract_checking(&self) -> bool {
+        self.contracts.get()
+    }
+
+    // where 📥 finds the source of modules that aren't loaded yet
+    pub fn set_module_loader(&self, loader: Rc<dyn module::module::ModuleLoader>) {
+        self.modules.borrow_mut().loader = Some(loader);
+    }
+
+    // bind in the innermost frame, shadowing outer bindings
+    pub fn define(&self, key: Symbol, value: NylispExpression) {
+        let value = self.store(value);
+        self.data.borrow_mut().insert(key, value);
+    }
+
+    // overwrite the binding in the frame that owns it, false if key is unbound
+    pub fn set(&self, key: Symbol, value: NylispExpression) -> bool {
+        if let Some(slot) = self.data.borrow_mut().get_mut(&key) {
+            *slot = self.store(value);
+            return true;
+        }
+        match &self._virtual {
+            Some(virtual_env) => virtual_env.set(key, value),
+            None => false,
+        }
+    }
+
+    // a closure kept in the frame it closes over would keep that frame alive forever,
+    // so it points at OWN_FRAME while stored and gets the frame back from load when it is read
+    fn store(&self, value: NylispExpression) -> NylispExpression {
+        match value {
+            NylispExpression::Closure { args, body, env, name, doc } if Rc::ptr_eq(&env.data, &self.data) => {
+                let env = Environment { data: OWN_FRAME.with(Rc::clone), _virtual: None, modules: env.modules, contracts: env.contracts };
+                NylispExpression::Closure { args, body, env, name, doc }
+            }
+            value => value,
+        }
+    }
+
+    pub(crate) fn load(&self, value: &NylispExpression) -> NylispExpression {
+        match value {
+            NylispExpression::Closure { args, body, env, name, doc } if OWN_FRAME.with(|own| Rc::ptr_eq(&env.data, own)) => {
+                NylispExpression::Closure { args: args.clone(), body: body.clone(), env: self.clone(), name: *name, doc: doc.clone() }
+            }
+            value => value.clone(),
+        }
+    }
+}
+
+// frames can hold closures pointing back at themselves, so don't print them
+impl std::fmt::Debug for Environment {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "<environment>")
+    }
+}
+
+impl PartialEq for Environment {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.data, &other.data)
+    }
+}
+
+// a name that was never interned can't be bound, so looking it up leaves the symbol table alone
 pub fn get(key: &str, env: &Environment) -> Option<NylispExpression> {
-    match env.data.get(key) {
-        Some(exp) => Some(exp.clone()),
+    match Symbol::lookup(key) {
+        Some(key) => get_symbol(key, env),
+        None => module::module::qualified(key, env),
+    }
+}
+
+// a name bound nowhere in the chain can still be module/name, an export of a loaded module
+pub fn get_symbol(key: Symbol, env: &Environment) -> Option<NylispExpression> {
+    lookup(key, env).or_else(|| module::module::qualified(key.as_str(), env))
+}
+
+fn lookup(key: Symbol, env: &Environment) -> Option<NylispExpression> {
+    match env.data.borrow().get(&key) {
+        Some(exp) => Some(env.load(exp)),
         None => {
             match &env._virtual {
-                Some(virtual_env) => get(key, virtual_env),
+                Some(virtual_env) => lookup(key, virtual_env),
                 None => None,
             }
         }
@@ -70,12 +531,43 @@ pub fn get(key: &str, env: &Environment) -> Option<NylispExpression> {
 #[derive(Clone, Debug, PartialEq)]
 pub enum NylispError {
     Because(String),
+    // error that escaped the body of a named closure, nesting these gives the call trace
+    InFunction(Symbol, Box<NylispError>),
+    // error object raised by 🧨 or by a builtin that knows what kind of error it is
+    Raised(NylispExpression),
+}
+
+impl NylispError {
+    // builtin error with a kind scripts can check, like 🔑type or 🔑unbound
+    pub fn of_kind(kind: &str, message: String) -> NylispError {
+        NylispError::Raised(NylispExpression::Error {
+            kind: kind.into(),
+            message,
+            payload: Rc::new(NylispExpression::Nil),
+        })
+    }
+
+    // the error object a 🎣 handler sees, untyped errors get 🔑error
+    pub fn to_expression(&self) -> NylispExpression {
+        match self {
+            NylispError::Because(message) => NylispExpression::Error {
+                kind: "error".into(),
+                message: message.clone(),
+                payload: Rc::new(NylispExpression::Nil),
+            },
+            NylispError::InFunction(_, e) => e.to_expression(),
+            NylispError::Raised(e) => e.clone(),
+        }
+    }
 }
 
 impl std::fmt::Display for NylispError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match *self {
             NylispError::Because(ref s) => write!(f, "{}", s),
+            NylispError::InFunction(ref name, ref e) => write!(f, "{}\n    in {}", e, name),
+            NylispError::Raised(NylispExpression::Error { ref message, .. }) => write!(f, "{}", message),
+            NylispError::Raised(ref e) => write!(f, "{}", e),
         }
     }
-}
\ No newline at end of file
+}
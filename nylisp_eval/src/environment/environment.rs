@@ -1,16 +1,48 @@
 use crate::ast;
-use crate::evaluation;
 use rand::Rng;
 use std::cell::RefCell;
 use std::collections::HashMap;
-use std::env::args;
 use std::rc::Rc;
+use unicode_segmentation::UnicodeSegmentation;
 
-pub fn builtin_env<'a>() -> ast::ast::Environment<'a> {
-    let mut data: HashMap<String, ast::ast::NylispExpression> = HashMap::new();
+thread_local! {
+    // evaluated once, every builtin environment gets the same closures
+    static PRELUDE: Result<Vec<(ast::ast::Symbol, ast::ast::NylispExpression)>, ast::ast::NylispError> = prelude();
+}
+
+pub fn builtin_env() -> ast::ast::Environment {
+    builtin_env_sharing(Rc::default())
+}
+
+// a module gets builtins of its own but shares the loaded modules with whoever imported it
+pub(crate) fn builtin_env_sharing(modules: Rc<RefCell<crate::module::module::Modules>>) -> ast::ast::Environment {
+    let mut data = builtins();
+    PRELUDE.with(|prelude| {
+        if let Ok(bindings) = prelude {
+            data.extend(bindings.iter().cloned());
+        }
+    });
+    ast::ast::Environment::sharing(data, modules)
+}
+
+// the stream functions are nylisp code on top of 💤, ⏰ and 🌊, evaluated in a frame of their own
+fn prelude() -> Result<Vec<(ast::ast::Symbol, ast::ast::NylispExpression)>, ast::ast::NylispError> {
+    let env = ast::ast::Environment::new(builtins()).extend(HashMap::new());
+    let tokens = crate::tokenizer::tokenizer::Tokenizer::new(super::prelude::STREAMS.to_string()).tokenize();
+    let forms = crate::parser::parser::Parser::new().parse_programs(tokens).into_iter().collect::<Result<Vec<_>, _>>()?;
+    for result in crate::evaluation::evaluation::Evaluator::new(forms).eval_programs(&env) {
+        result?;
+    }
+
+    let bindings = env.data.borrow().iter().map(|(name, value)| (*name, env.load(value))).collect();
+    Ok(bindings)
+}
+
+fn builtins() -> HashMap<ast::ast::Symbol, ast::ast::NylispExpression> {
+    let mut data: HashMap<ast::ast::Symbol, ast::ast::NylispExpression> = HashMap::new();
 
     // +
-    data.insert("+".to_string(), ast::ast::NylispExpression::Function(
+    data.insert("+".into(), ast::ast::NylispExpression::Function(
         |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
             let sum = parse_list_of_floats(&args)?.iter().fold(0.0, |sum, a| sum + a);
 
@@ -19,7 +51,7 @@ pub fn builtin_env<'a>() -> ast::ast::Environment<'a> {
     ));
 
     // -
-    data.insert("-".to_string(), ast::ast::NylispExpression::Function(
+    data.insert("-".into(), ast::ast::NylispExpression::Function(
         |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
             let args = parse_list_of_floats(&args)?;
             // pop the first element
@@ -34,7 +66,7 @@ pub fn builtin_env<'a>() -> ast::ast::Environment<'a> {
     ));
 
     // *
-    data.insert("*".to_string(), ast::ast::NylispExpression::Function(
+    data.insert("*".into(), ast::ast::NylispExpression::Function(
         |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
             let args = parse_list_of_floats(&args)?;
             // pop the first element
@@ -49,7 +81,7 @@ pub fn builtin_env<'a>() -> ast::ast::Environment<'a> {
     ));
 
     // /
-    data.insert("/".to_string(), ast::ast::NylispExpression::Function(
+    data.insert("/".into(), ast::ast::NylispExpression::Function(
         |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
             let args = parse_list_of_floats(&args)?;
             // pop the first element
@@ -63,7 +95,7 @@ pub fn builtin_env<'a>() -> ast::ast::Environment<'a> {
     ));
 
     // %
-    data.insert("%".to_string(), ast::ast::NylispExpression::Function(
+    data.insert("%".into(), ast::ast::NylispExpression::Function(
         |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
             let args = parse_list_of_floats(&args)?;
             // pop the first element
@@ -78,7 +110,7 @@ pub fn builtin_env<'a>() -> ast::ast::Environment<'a> {
     ));
 
     // =
-    data.insert("=".to_string(), ast::ast::NylispExpression::Function(
+    data.insert("=".into(), ast::ast::NylispExpression::Function(
         |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
             let first = args.first().unwrap();
             let rest = args.iter().skip(1);
@@ -93,8 +125,13 @@ pub fn builtin_env<'a>() -> ast::ast::Environment<'a> {
     ));
 
     // <
-    data.insert("<".to_string(), ast::ast::NylispExpression::Function(
+    data.insert("<".into(), ast::ast::NylispExpression::Function(
         |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            // characters compare by codepoint
+            if let Some(ast::ast::NylispExpression::Char(_)) = args.first() {
+                let args = parse_list_of_chars(&args)?;
+                return Ok(ast::ast::NylispExpression::Boolean(args.windows(2).all(|w| w[0] < w[1])));
+            }
             let args = parse_list_of_floats(&args)?;
             // pop the first element
             let mut result = *args.first().unwrap();
@@ -111,8 +148,13 @@ pub fn builtin_env<'a>() -> ast::ast::Environment<'a> {
     ));
 
     // >
-    data.insert(">".to_string(), ast::ast::NylispExpression::Function(
+    data.insert(">".into(), ast::ast::NylispExpression::Function(
         |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            // characters compare by codepoint
+            if let Some(ast::ast::NylispExpression::Char(_)) = args.first() {
+                let args = parse_list_of_chars(&args)?;
+                return Ok(ast::ast::NylispExpression::Boolean(args.windows(2).all(|w| w[0] > w[1])));
+            }
             let args = parse_list_of_floats(&args)?;
             // pop the first element
             let mut result = *args.first().unwrap();
@@ -128,76 +170,341 @@ pub fn builtin_env<'a>() -> ast::ast::Environment<'a> {
         }
     ));
 
-    // and
-    data.insert("😎".to_string(), ast::ast::NylispExpression::Function(
+    // not
+    data.insert("❌".into(), ast::ast::NylispExpression::Function(
         |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
-            let mut args = parse_list_of_bools(&args)?;
-            // pop the first element
-            let result = args.pop().unwrap();
+            let args = parse_list_of_bools(&args)?;
+            let mut result: Vec<ast::ast::NylispExpression> = vec![];
             for a in args {
-                if !a {
-                    return Ok(ast::ast::NylispExpression::Boolean(false));
+                result.push(ast::ast::NylispExpression::Boolean(!a));
+            }
+
+            Ok(ast::ast::NylispExpression::List(result))
+        }
+    ));
+
+    // car
+    data.insert("🚗".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            // validate
+            if args.len() != 1 {
+                return Err(ast::ast::NylispError::Because(
+                    "🚗 requires exactly one argument".to_string(),
+                ));
+            }
+            // get first element of list, without walking or copying the rest
+            let first = match &args[0] {
+                ast::ast::NylispExpression::Pair(car, _) => Some(&**car),
+                ast::ast::NylispExpression::List(list) => list.first(),
+                ast::ast::NylispExpression::Tail(tail) => tail.first(),
+                _ => return Err(ast::ast::NylispError::of_kind("type", "expected a list".to_string())),
+            };
+            match first {
+                Some(first) => Ok(first.clone()),
+                None => Err(ast::ast::NylispError::Because(
+                    "🚗 requires a non-empty list".to_string(),
+                )),
+            }
+        }
+    ));
+
+    // cdr
+    data.insert("💭".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            if args.len() != 1 {
+                return Err(ast::ast::NylispError::Because(
+                    "💭 requires exactly one argument".to_string(),
+                ));
+            }
+            // the rest is shared, not copied, a list moves into the tail that points into it
+            let mut args = args;
+            match args.pop().unwrap() {
+                ast::ast::NylispExpression::Pair(_, cdr) => Ok((*cdr).clone()),
+                ast::ast::NylispExpression::List(list) if !list.is_empty() => Ok(ast::ast::NylispExpression::tail(Rc::new(list), 1)),
+                ast::ast::NylispExpression::Tail(tail) => Ok(tail.rest()),
+                ast::ast::NylispExpression::List(_) => Err(ast::ast::NylispError::Because(
+                    "💭 requires a non-empty list".to_string(),
+                )),
+                _ => Err(ast::ast::NylispError::of_kind("type", "expected a list".to_string())),
+            }
+        }
+    ));
+
+    // cons
+    data.insert("🍡".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            if args.len() != 2 {
+                return Err(ast::ast::NylispError::Because(
+                    "🍡 requires exactly two arguments".to_string(),
+                ));
+            }
+            let mut args = args.into_iter();
+            let car = args.next().unwrap();
+            let cdr = args.next().unwrap();
+            Ok(ast::ast::NylispExpression::Pair(Rc::new(car), Rc::new(cdr)))
+        }
+    ));
+
+    // list
+    data.insert("📜".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            Ok(ast::ast::NylispExpression::List(args))
+        }
+    ));
+
+    // append
+    data.insert("🚃".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            let mut args = args;
+            // the last argument becomes the shared tail, everything before it is copied
+            let mut result = match args.pop() {
+                Some(last) => last,
+                None => return Ok(ast::ast::NylispExpression::List(vec![])),
+            };
+            for arg in args.iter().rev() {
+                for exp in parse_single_list(arg)?.into_iter().rev() {
+                    result = ast::ast::NylispExpression::Pair(Rc::new(exp), Rc::new(result));
                 }
             }
 
-            Ok(ast::ast::NylispExpression::Boolean(result))
+            Ok(result)
         }
     ));
 
-    // or
-    data.insert("😕".to_string(), ast::ast::NylispExpression::Function(
+    // reverse
+    data.insert("🔙".into(), ast::ast::NylispExpression::Function(
         |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
-            let mut args = parse_list_of_bools(&args)?;
-            // pop the first element
-            let result = args.pop().unwrap();
-            for a in args {
-                if a {
-                    return Ok(ast::ast::NylispExpression::Boolean(true));
+            if args.len() != 1 {
+                return Err(ast::ast::NylispError::Because(
+                    "🔙 requires exactly one argument".to_string(),
+                ));
+            }
+            let mut list = parse_single_list(&args[0])?;
+            list.reverse();
+            Ok(ast::ast::NylispExpression::List(list))
+        }
+    ));
+
+    // length
+    data.insert("📏".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            if args.len() != 1 {
+                return Err(ast::ast::NylispError::Because(
+                    "📏 requires exactly one argument".to_string(),
+                ));
+            }
+            let list = parse_single_list(&args[0])?;
+            Ok(ast::ast::NylispExpression::Number(list.len() as f64))
+        }
+    ));
+
+    // null?
+    data.insert("🈳".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            if args.len() != 1 {
+                return Err(ast::ast::NylispError::Because(
+                    "🈳 requires exactly one argument".to_string(),
+                ));
+            }
+            match &args[0] {
+                ast::ast::NylispExpression::List(list) => Ok(ast::ast::NylispExpression::Boolean(list.is_empty())),
+                _ => Ok(ast::ast::NylispExpression::Boolean(false)),
+            }
+        }
+    ));
+
+    // string->list
+    data.insert("🧶".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            if args.len() != 1 {
+                return Err(ast::ast::NylispError::Because(
+                    "🧶 requires exactly one argument".to_string(),
+                ));
+            }
+            let text = parse_single_text(&args[0])?;
+            Ok(ast::ast::NylispExpression::List(
+                text.graphemes(true).map(|g| ast::ast::NylispExpression::Char(g.to_string())).collect(),
+            ))
+        }
+    ));
+
+    // list->string
+    data.insert("🧵".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            if args.len() != 1 {
+                return Err(ast::ast::NylispError::Because(
+                    "🧵 requires exactly one argument".to_string(),
+                ));
+            }
+            let chars = parse_list_of_chars(&parse_single_list(&args[0])?)?;
+            Ok(ast::ast::NylispExpression::String(chars.concat()))
+        }
+    ));
+
+    // emoji?
+    data.insert("🎭".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            let chars = parse_list_of_chars(&args)?;
+            Ok(ast::ast::NylispExpression::Boolean(chars.iter().all(|c| is_emoji(c))))
+        }
+    ));
+
+    // digit?
+    data.insert("🔢".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            let chars = parse_list_of_chars(&args)?;
+            Ok(ast::ast::NylispExpression::Boolean(
+                chars.iter().all(|c| !is_emoji(c) && c.chars().next().unwrap().is_numeric()),
+            ))
+        }
+    ));
+
+    // alphabetic?
+    data.insert("🔠".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            let chars = parse_list_of_chars(&args)?;
+            Ok(ast::ast::NylispExpression::Boolean(
+                chars.iter().all(|c| c.chars().next().unwrap().is_alphabetic()),
+            ))
+        }
+    ));
+
+    // char->codepoints
+    data.insert("🧮".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            if args.len() != 1 {
+                return Err(ast::ast::NylispError::Because(
+                    "🧮 requires exactly one argument".to_string(),
+                ));
+            }
+            // a grapheme cluster may span several codepoints, so this is always a list
+            let c = parse_single_char(&args[0])?;
+            Ok(ast::ast::NylispExpression::List(
+                c.chars().map(|ch| ast::ast::NylispExpression::Number(ch as u32 as f64)).collect(),
+            ))
+        }
+    ));
+
+    // codepoints->char
+    data.insert("🪄".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            let mut c = String::new();
+            for n in parse_list_of_floats(&args)? {
+                match char::from_u32(n as u32) {
+                    Some(ch) if n.fract() == 0.0 && n >= 0.0 => c.push(ch),
+                    _ => return Err(ast::ast::NylispError::Because(
+                        format!("🪄 got an invalid codepoint {}", n),
+                    )),
                 }
             }
+            if c.graphemes(true).count() != 1 {
+                return Err(ast::ast::NylispError::Because(
+                    "🪄 requires codepoints forming exactly one character".to_string(),
+                ));
+            }
+            Ok(ast::ast::NylispExpression::Char(c))
+        }
+    ));
 
-            Ok(ast::ast::NylispExpression::Boolean(result))
+    // documentation
+    data.insert("📖".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            if args.len() != 1 {
+                return Err(ast::ast::NylispError::Because(
+                    "📖 requires exactly one argument".to_string(),
+                ));
+            }
+            match &args[0] {
+                ast::ast::NylispExpression::Closure { doc: Some(doc), .. } => Ok(ast::ast::NylispExpression::String(doc.clone())),
+                ast::ast::NylispExpression::Macro(transformer) => match &**transformer {
+                    ast::ast::NylispExpression::Closure { doc: Some(doc), .. } => Ok(ast::ast::NylispExpression::String(doc.clone())),
+                    _ => Ok(ast::ast::NylispExpression::Nil),
+                },
+                _ => Ok(ast::ast::NylispExpression::Nil),
+            }
         }
     ));
 
-    // not
-    data.insert("❌".to_string(), ast::ast::NylispExpression::Function(
+    // gensym, a fresh symbol that can't clash with the names a macro user picked
+    data.insert("🆕".into(), ast::ast::NylispExpression::Function(
         |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
-            let args = parse_list_of_bools(&args)?;
-            let mut result: Vec<ast::ast::NylispExpression> = vec![];
-            for a in args {
-                result.push(ast::ast::NylispExpression::Boolean(!a));
+            let prefix = match args.as_slice() {
+                [] => "🆕".to_string(),
+                [ast::ast::NylispExpression::Symbol(s)] => s.to_string(),
+                [ast::ast::NylispExpression::String(s)] => s.clone(),
+                _ => return Err(ast::ast::NylispError::Because(
+                    "🆕 requires no arguments, or a symbol or string prefix".to_string(),
+                )),
+            };
+            Ok(ast::ast::NylispExpression::Symbol(ast::ast::Symbol::gensym(&prefix)))
+        }
+    ));
+
+    // make-coroutine, 🎬 starts it by calling the function with its arguments
+    data.insert("🐣".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            match args.as_slice() {
+                [f @ (ast::ast::NylispExpression::Closure { .. } | ast::ast::NylispExpression::Function(_))] => Ok(
+                    ast::ast::NylispExpression::Coroutine(Rc::new(RefCell::new(ast::ast::CoroutineState::Fresh(f.clone())))),
+                ),
+                _ => Err(ast::ast::NylispError::Because(
+                    "🐣 requires exactly one function".to_string(),
+                )),
             }
+        }
+    ));
 
-            Ok(ast::ast::NylispExpression::List(result))
+    // coroutine-status, 🔑suspended, 🔑running or 🔑dead
+    data.insert("🚥".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            match args.as_slice() {
+                [ast::ast::NylispExpression::Coroutine(state)] => Ok(ast::ast::NylispExpression::Keyword(state.borrow().status().into())),
+                _ => Err(ast::ast::NylispError::of_kind(
+                    "type",
+                    "🚥 requires exactly one coroutine".to_string(),
+                )),
+            }
         }
     ));
 
-    // car
-    data.insert("🚗".to_string(), ast::ast::NylispExpression::Function(
+    // error?
+    data.insert("🚨".into(), ast::ast::NylispExpression::Function(
         |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
-            // validate
             if args.len() != 1 {
                 return Err(ast::ast::NylispError::Because(
-                    "🚗 requires exactly one argument".to_string(),
+                    "🚨 requires exactly one argument".to_string(),
                 ));
             }
-            // get first element of list
-            let cons = parse_single_list(&args[0])?;
-            Ok(cons.first().unwrap().clone())
+            Ok(ast::ast::NylispExpression::Boolean(matches!(args[0], ast::ast::NylispExpression::Error { .. })))
         }
     ));
 
-    // cdr
-    data.insert("💭".to_string(), ast::ast::NylispExpression::Function(
+    // error-kind
+    data.insert("🆔".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            let (kind, _, _) = parse_single_error("🆔", &args)?;
+            Ok(ast::ast::NylispExpression::Keyword(kind))
+        }
+    ));
+
+    // error-message
+    data.insert("📨".into(), ast::ast::NylispExpression::Function(
         |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
-            let cons = parse_single_list(&args[0])?;
-            Ok(ast::ast::NylispExpression::List(cons[1..].to_vec()))
+            let (_, message, _) = parse_single_error("📨", &args)?;
+            Ok(ast::ast::NylispExpression::String(message))
+        }
+    ));
+
+    // error-payload
+    data.insert("🎁".into(), ast::ast::NylispExpression::Function(
+        |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+            let (_, _, payload) = parse_single_error("🎁", &args)?;
+            Ok(payload)
         }
     ));
 
     // random
-    data.insert("🎨".to_string(), ast::ast::NylispExpression::Function(
+    data.insert("🎨".into(), ast::ast::NylispExpression::Function(
         |args: Vec<ast::ast::NylispExpression>| -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
             if let ast::ast::NylispExpression::Number(n) = &args[0] {
                 let index = rand::thread_rng().gen_range(0, *n as usize);
@@ -211,22 +518,20 @@ pub fn builtin_env<'a>() -> ast::ast::Environment<'a> {
     ));
 
     // return data
-    ast::ast::Environment {
-        data,
-        _virtual: None,
-    }
+    data
 }
 
 fn parse_list_of_floats(
     args: &[ast::ast::NylispExpression],
 ) -> Result<Vec<f64>, ast::ast::NylispError> {
-    args.iter().map(|x| parse_single_float(x)).collect()
+    args.iter().map(parse_single_float).collect()
 }
 
 fn parse_single_float(exp: &ast::ast::NylispExpression) -> Result<f64, ast::ast::NylispError> {
     match exp {
         ast::ast::NylispExpression::Number(num) => Ok(*num),
-        _ => Err(ast::ast::NylispError::Because(
+        _ => Err(ast::ast::NylispError::of_kind(
+            "type",
             "expected a number".to_string(),
         )),
     }
@@ -235,13 +540,14 @@ fn parse_single_float(exp: &ast::ast::NylispExpression) -> Result<f64, ast::ast:
 fn parse_list_of_bools(
     args: &[ast::ast::NylispExpression],
 ) -> Result<Vec<bool>, ast::ast::NylispError> {
-    args.iter().map(|x| parse_single_bool(x)).collect()
+    args.iter().map(parse_single_bool).collect()
 }
 
 fn parse_single_bool(exp: &ast::ast::NylispExpression) -> Result<bool, ast::ast::NylispError> {
     match exp {
         ast::ast::NylispExpression::Boolean(b) => Ok(*b),
-        _ => Err(ast::ast::NylispError::Because(
+        _ => Err(ast::ast::NylispError::of_kind(
+            "type",
             "expected a boolean".to_string(),
         )),
     }
@@ -250,10 +556,67 @@ fn parse_single_bool(exp: &ast::ast::NylispExpression) -> Result<bool, ast::ast:
 fn parse_single_list(
     exp: &ast::ast::NylispExpression,
 ) -> Result<Vec<ast::ast::NylispExpression>, ast::ast::NylispError> {
-    match exp {
-        ast::ast::NylispExpression::List(list) => Ok(list.clone()),
-        _ => Err(ast::ast::NylispError::Because(
+    match exp.unroll() {
+        Some((list, None)) => Ok(list),
+        Some((_, Some(_))) => Err(ast::ast::NylispError::of_kind(
+            "type",
+            "expected a proper list, got a dotted pair".to_string(),
+        )),
+        None => Err(ast::ast::NylispError::of_kind(
+            "type",
             "expected a list".to_string(),
         )),
     }
 }
+
+fn parse_list_of_chars(
+    args: &[ast::ast::NylispExpression],
+) -> Result<Vec<String>, ast::ast::NylispError> {
+    args.iter().map(parse_single_char).collect()
+}
+
+fn parse_single_char(exp: &ast::ast::NylispExpression) -> Result<String, ast::ast::NylispError> {
+    match exp {
+        ast::ast::NylispExpression::Char(c) => Ok(c.clone()),
+        _ => Err(ast::ast::NylispError::of_kind(
+            "type",
+            "expected a character".to_string(),
+        )),
+    }
+}
+
+// symbols are how text is usually written in nylisp, so they count as strings here
+fn parse_single_text(exp: &ast::ast::NylispExpression) -> Result<String, ast::ast::NylispError> {
+    match exp {
+        ast::ast::NylispExpression::String(s) => Ok(s.clone()),
+        ast::ast::NylispExpression::Symbol(s) => Ok(s.to_string()),
+        _ => Err(ast::ast::NylispError::of_kind(
+            "type",
+            "expected a string".to_string(),
+        )),
+    }
+}
+
+fn is_emoji(c: &str) -> bool {
+    // variation selector 16 and keycaps turn plain characters like ❤ or 1 into emoji
+    if c.chars().any(|ch| ch == '\u{FE0F}' || ch == '\u{20E3}') {
+        return true;
+    }
+    matches!(
+        c.chars().next().unwrap() as u32,
+        0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2300..=0x23FF | 0x2B00..=0x2BFF | 0x3030 | 0x303D | 0x3297 | 0x3299
+    )
+}
+
+fn parse_single_error(
+    name: &str,
+    args: &[ast::ast::NylispExpression],
+) -> Result<(ast::ast::Symbol, String, ast::ast::NylispExpression), ast::ast::NylispError> {
+    match args {
+        [ast::ast::NylispExpression::Error { kind, message, payload }] => Ok((*kind, message.clone(), (**payload).clone())),
+        _ => Err(ast::ast::NylispError::of_kind(
+            "type",
+            format!("{} requires exactly one error object", name),
+        )),
+    }
+}
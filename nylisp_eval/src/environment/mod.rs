@@ -1 +1,2 @@
-pub mod environment;
\ No newline at end of file
+pub mod environment;
+mod prelude;
@@ -0,0 +1,32 @@
+// builtins written in nylisp itself, evaluated once and shared by every builtin environment
+
+// a stream is a 💤 promise of either 💖💔 or a pair of an element and the rest of the stream, 🌊 makes one
+pub(crate) const STREAMS: &str = "
+💖🍳 🤏 💖n s💔 💬the first n elements of stream s as a list💬
+    💖🐶 💖< n 1💔 😪💖💔
+        💖🍙 💖💖c 💖⏰ s💔💔💔
+            💖🐶 💖🈳 c💔 😪💖💔 💖🍡 💖🚗 c💔 💖🤏 💖- n 1💔 💖💭 c💔💔💔💔💔💔💔
+
+💖🍳 🦘 💖n s💔 💬stream s without its first n elements💬
+    💖💤 💖🍙 💖💖c 💖⏰ s💔💔💔
+        💖🐶 💖😕 💖< n 1💔 💖🈳 c💔💔 c 💖⏰ 💖🦘 💖- n 1💔 💖💭 c💔💔💔💔💔💔💔
+
+💖🍳 🏭 💖f s💔 💬stream of f applied to every element of stream s💬
+    💖💤 💖🍙 💖💖c 💖⏰ s💔💔💔
+        💖🐶 💖🈳 c💔 😪💖💔 💖🍡 💖f 💖🚗 c💔💔 💖🏭 f 💖💭 c💔💔💔💔💔💔💔
+
+💖🍳 🧺 💖keep? s💔 💬stream of the elements of stream s that keep? is true for💬
+    💖💤 💖🍙 💖💖c 💖⏰ s💔💔💔
+        💖🐶 💖🈳 c💔 😪💖💔
+            💖🐶 💖keep? 💖🚗 c💔💔
+                💖🍡 💖🚗 c💔 💖🧺 keep? 💖💭 c💔💔💔
+                💖⏰ 💖🧺 keep? 💖💭 c💔💔💔💔💔💔💔💔
+
+💖🍳 🪜 💖from ❓ to 💖step 1💔💔 💬stream of numbers from from, by step, stopping before to if it is given💬
+    💖🐶 💖😎 to 💖🐶 💖< step 0💔 💖😕 💖< from to💔 💖= from to💔💔 💖😕 💖> from to💔 💖= from to💔💔💔💔
+        💖💤 😪💖💔💔
+        💖🌊 from 💖🪜 💖+ from step💔 to step💔💔💔💔
+
+💖🍳 🌀 💖f x💔 💬endless stream of x, f of x, f of f of x and so on💬
+    💖🌊 x 💖🌀 f 💖f x💔💔💔💔
+";
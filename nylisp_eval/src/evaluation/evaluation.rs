@@ -1,10 +1,52 @@
-use std::borrow::BorrowMut;
-use std::cell::RefCell;
 use std::rc::Rc;
 use crate::ast::*;
+use crate::evaluation::frame::{Continuation, Frame};
+use crate::expander;
+use crate::module;
 use crate::tokenizer;
 use crate::parser;
 
+// parameter list of a 🐷 closure, split by ❓, 🔐 and the 🔗 rest parameter
+struct ClosureParams {
+    // a symbol, or a list pattern destructuring the argument
+    required: Vec<ast::NylispExpression>,
+    optional: Vec<(ast::Symbol, ast::NylispExpression)>,
+    keys: Vec<(ast::Symbol, ast::NylispExpression)>,
+    rest: Option<ast::Symbol>,
+    // 📐 after a parameter name, a type name or a predicate its argument has to satisfy
+    contracts: Vec<(ast::Symbol, ast::NylispExpression)>,
+}
+
+impl ClosureParams {
+    // how many arguments are accepted, for arity errors
+    fn arity(&self) -> String {
+        let min = self.required.len();
+        let max = min + self.optional.len();
+        let mut shape = if self.rest.is_some() {
+            format!("at least {} arguments", min)
+        } else if min == max {
+            format!("exactly {} arguments", min)
+        } else {
+            format!("{} to {} arguments", min, max)
+        };
+        if !self.keys.is_empty() {
+            let keys: Vec<String> = self.keys.iter().map(|(name, _)| format!("{}{}", tokenizer::tokenizer::KEYWORD, name)).collect();
+            shape.push_str(&format!(" plus keywords {}", keys.join(" ")));
+        }
+        shape
+    }
+}
+
+// (local name, exported name) pairs a 📥 binds
+type Imports = Vec<(ast::Symbol, ast::Symbol)>;
+
+// one step of the evaluator, what happens to a value is decided by the continuation
+enum Step {
+    Eval(ast::NylispExpression, ast::Environment),
+    Return(ast::NylispExpression),
+    Raise(ast::NylispError),
+}
+
 #[derive(Debug, Clone)]
 pub struct Evaluator {
     program: Vec<ast::NylispExpression>,
@@ -24,135 +66,734 @@ impl Evaluator {
         }
     }
 
-    pub fn eval_programs(&mut self, env: &mut ast::Environment) -> Vec<Result<ast::NylispExpression, ast::NylispError>> {
+    pub fn eval_programs(&mut self, env: &ast::Environment) -> Vec<Result<ast::NylispExpression, ast::NylispError>> {
+        // 🧬 macros are expanded in a pass of their own, before any program runs
+        let expanded = expander::expander::Expander::new(env).expand_programs(&self.program);
         let mut result: Vec<Result<ast::NylispExpression, ast::NylispError>> = Vec::new();
-        for expr in self.program.iter() {
-            result.push(self.evaluate(expr, env));
+        for expr in expanded {
+            result.push(expr.and_then(|expr| self.evaluate(&expr, env)));
         }
 
         result
     }
 
-    fn evaluate(&self, exp: &ast::NylispExpression, env: &'_ mut ast::Environment) -> Result<ast::NylispExpression, ast::NylispError> {
+    // pending work is kept in heap frames instead of on the rust stack,
+    // so deep recursion doesn't overflow and 🔖 can capture the rest of the computation
+    fn evaluate(&self, exp: &ast::NylispExpression, env: &ast::Environment) -> Result<ast::NylispExpression, ast::NylispError> {
+        let mut k = Continuation::default();
+        let mut step = Step::Eval(exp.clone(), env.clone());
+        loop {
+            step = match step {
+                Step::Eval(exp, env) => self.eval_step(exp, env, &mut k),
+                Step::Return(value) => match k.pop() {
+                    Some(frame) => self.resume(frame, value, &mut k),
+                    None => return Ok(value),
+                },
+                Step::Raise(e) => self.unwind(e, &mut k)?,
+            }
+        }
+    }
+
+    fn eval_step(&self, exp: ast::NylispExpression, env: ast::Environment, k: &mut Continuation) -> Step {
         match exp {
-            ast::NylispExpression::Quote(q) => Ok((**q).clone()),
-            ast::NylispExpression::Boolean(b) => Ok(ast::NylispExpression::Boolean(*b)),
-            ast::NylispExpression::Number(n) => Ok(ast::NylispExpression::Number(*n)),
-            ast::NylispExpression::String(s) => Ok(ast::NylispExpression::String(s.clone())),
+            ast::NylispExpression::Quote(q) => Step::Return((*q).clone()),
+            ast::NylispExpression::Boolean(_)
+            | ast::NylispExpression::Number(_)
+            | ast::NylispExpression::String(_)
+            | ast::NylispExpression::Char(_)
+            | ast::NylispExpression::Keyword(_)
+            | ast::NylispExpression::Nil
+            | ast::NylispExpression::Error { .. }
+            | ast::NylispExpression::Record { .. }
+            | ast::NylispExpression::Promise(_)
+            | ast::NylispExpression::Coroutine(_)
+            | ast::NylispExpression::Function(_) => Step::Return(exp),
             ast::NylispExpression::Symbol(s) => {
                 // get from env
-                match ast::get(s.as_str(), env) {
-                    Some(e) => Ok(e),
-                    None => Err(ast::NylispError::Because(format!("symbol {} not found in environment", s)))
+                match ast::get_symbol(s, &env) {
+                    Some(e) => Step::Return(e),
+                    None => Step::Raise(ast::NylispError::of_kind("unbound", format!("symbol {} not found in environment", s)))
                 }
             }
-            ast::NylispExpression::Function(f) => Ok(ast::NylispExpression::Function(*f)),
             ast::NylispExpression::List(l) => {
                 let first: ast::NylispExpression = match l.first() {
                     Some(e) => e.clone(),
-                    None => return Ok(ast::NylispExpression::Boolean(false))
+                    None => return Step::Return(ast::NylispExpression::List(vec![]))
                 };
-                let rest: Vec<ast::NylispExpression> = l[1..].iter().map(|e| e.clone()).collect::<Vec<ast::NylispExpression>>();
-
-                // TODO: dirty as fuck but works
-                match self.wait_a_minute_is_this_a_special_form(first.clone(), rest.clone(), env) {
-                    Ok(e) => {
-                        match e {
-                            Some(e) => Ok(e),
-                            None => {
-                                match self.evaluate(&first, env) {
-                                    Ok(e) => {
-                                        match e {
-                                            ast::NylispExpression::Function(f) => {
-                                                let mut evaluated_args: Vec<ast::NylispExpression> = Vec::new();
-                                                for arg in rest {
-                                                    evaluated_args.push(self.evaluate(&arg, env)?);
-                                                }
-                                                f(evaluated_args)
-                                            }
-                                            ast::NylispExpression::Closure { args, body: cl_body } => {
-                                                self.evaluate(&*cl_body, &mut self.new_closure_env(args, rest, env).unwrap())
-                                            }
-                                            _ => Err(ast::NylispError::Because(format!("not a function: {:?}", first)))
-                                        }
-                                    }
-                                    Err(e) => Err(e)
-                                }
+
+                match self.wait_a_minute_is_this_a_special_form(&first, &l[1..], &env, k) {
+                    Ok(Some(step)) => step,
+                    Ok(None) => match self.macro_for(&first, &env) {
+                        Some(syntax @ ast::NylispExpression::SyntaxRules { .. }) => {
+                            // the pass before eval only sees the program text and 🧬 programs, code built while running,
+                            // like a 🧙 expansion or a quoted form handed to eval, and uses of a 🧬 bound inside a body reach here instead
+                            let expander = expander::expander::Expander::new(&env);
+                            match expander.transcribe(&syntax, &ast::NylispExpression::List(l.clone())).and_then(|e| expander.expand(&e)) {
+                                Ok(expanded) => Step::Eval(expanded, env),
+                                Err(e) => Step::Raise(e),
+                            }
+                        }
+                        Some(transformer) => {
+                            // the transformer sees the arguments as written, its result is evaluated in place of the call
+                            k.push(Frame::Expand { env });
+                            self.apply(transformer, l[1..].to_vec(), &first, k)
+                        }
+                        None => self.call(Rc::new(l), vec![], env, k),
+                    },
+                    Err(e) => Step::Raise(e),
+                }
+            }
+            // code built by macros with 🍡, 🚃 and 💭 comes as pairs and tails
+            ast::NylispExpression::Pair(_, _) | ast::NylispExpression::Tail(_) => match exp.proper_list() {
+                Some(items) => Step::Eval(ast::NylispExpression::List(items), env),
+                None => Step::Raise(ast::NylispError::Because(format!("can't evaluate the dotted list {}", exp))),
+            },
+            _ => Step::Raise(ast::NylispError::Because(format!("unsupported expression type: {:?}", exp)))
+        }
+    }
+
+    // hand value to the frame that was waiting for it
+    fn resume(&self, frame: Frame, value: ast::NylispExpression, k: &mut Continuation) -> Step {
+        match frame {
+            Frame::If { then, otherwise, env } => {
+                if value.is_truthy() {
+                    Step::Eval(then, env)
+                } else {
+                    Step::Eval(otherwise, env)
+                }
+            }
+            Frame::Define { name, env } => {
+                env.define(name, value);
+                Step::Return(ast::NylispExpression::Nil)
+            }
+            Frame::Set { name, env } => {
+                if env.set(name, value) {
+                    Step::Return(ast::NylispExpression::Nil)
+                } else {
+                    Step::Raise(ast::NylispError::Because(format!("📝 can't set {}, it is not bound", name)))
+                }
+            }
+            Frame::Sequence { exps, next, env } => self.eval_sequence(exps, next, env, k),
+            Frame::Args { exps, mut done, env } => {
+                done.push(value);
+                self.call(exps, done, env, k)
+            }
+            Frame::Logic { and, exps, next, env } => {
+                // 😎 stops at the first falsy value, 😕 at the first truthy one
+                if value.is_truthy() != and {
+                    Step::Return(value)
+                } else {
+                    self.logic(and, exps, next, env, k)
+                }
+            }
+            Frame::Cond { clauses, next, env } => {
+                if !value.is_truthy() {
+                    return self.cond(clauses, next + 1, env, k);
+                }
+                let clause = match self.clause(tokenizer::tokenizer::COND, &clauses[next]) {
+                    Ok(clause) => clause,
+                    Err(e) => return Step::Raise(e),
+                };
+                if clause.len() == 1 {
+                    return Step::Return(value);
+                }
+                self.eval_sequence(Rc::new(clause[1..].to_vec()), 0, env, k)
+            }
+            Frame::Case { clauses, env } => {
+                // compare the key against each clause's literal values, nothing in there is evaluated
+                for clause in clauses.iter() {
+                    let clause = match self.clause(tokenizer::tokenizer::CASE, clause) {
+                        Ok(clause) => clause,
+                        Err(e) => return Step::Raise(e),
+                    };
+                    let matched = if self.is_else(&clause[0]) {
+                        true
+                    } else if let ast::NylispExpression::List(values) = &clause[0] {
+                        values.contains(&value)
+                    } else {
+                        return Step::Raise(ast::NylispError::Because(format!("🔀 requires a list of values to match, got {:?}", clause[0])))
+                    };
+                    if matched {
+                        return self.eval_sequence(Rc::new(clause[1..].to_vec()), 0, env, k);
+                    }
+                }
+                Step::Return(ast::NylispExpression::Nil)
+            }
+            Frame::Try { finally: Some(exps), env, .. } | Frame::Finally { exps, env } => {
+                // cleanup always runs, and its own error wins over the result
+                k.push(Frame::Restore(value));
+                self.eval_sequence(exps, 0, env, k)
+            }
+            Frame::Try { finally: None, .. } | Frame::Call(_) | Frame::Reset => Step::Return(value),
+            Frame::Restore(saved) => Step::Return(saved),
+            Frame::Reraise(e) => Step::Raise(e),
+            Frame::Expand { env } => Step::Eval(value, env),
+            Frame::MacroExpand { env } => {
+                let (head, args) = match &value {
+                    ast::NylispExpression::List(l) if !l.is_empty() => (l[0].clone(), l[1..].to_vec()),
+                    _ => return Step::Return(value),
+                };
+                match self.macro_for(&head, &env) {
+                    Some(syntax @ ast::NylispExpression::SyntaxRules { .. }) => {
+                        // 🔍 works on a value, so its expansion can only happen once that value exists
+                        match expander::expander::Expander::new(&env).transcribe(&syntax, &value) {
+                            Ok(expanded) => {
+                                k.push(Frame::MacroExpand { env });
+                                Step::Return(expanded)
                             }
+                            Err(e) => Step::Raise(e),
                         }
                     }
-                    Err(e) => Err(e)
+                    Some(transformer) => {
+                        k.push(Frame::MacroExpand { env });
+                        self.apply(transformer, args, &head, k)
+                    }
+                    None => Step::Return(value),
+                }
+            }
+            Frame::Match { clauses, env } => self.match_clauses(value, clauses, 0, env, k),
+            Frame::Guard { subject, clauses, next, env, scope } => {
+                if !value.is_truthy() {
+                    return self.match_clauses(subject, clauses, next + 1, env, k);
+                }
+                // the clause was checked before its guard ran
+                let body = match &clauses[next] {
+                    ast::NylispExpression::List(clause) => clause[3..].to_vec(),
+                    _ => vec![],
+                };
+                self.eval_sequence(Rc::new(body), 0, scope, k)
+            }
+            Frame::Coroutine(state) => {
+                // the body returned, there is nothing left to resume
+                *state.borrow_mut() = ast::CoroutineState::Dead;
+                Step::Return(value)
+            }
+            Frame::Yield => {
+                let segment = match k.split_at(|frame| matches!(frame, Frame::Coroutine(_))) {
+                    Some(segment) => segment,
+                    None => return Step::Raise(ast::NylispError::Because("🫳 can only be used inside a coroutine".to_string())),
+                };
+                if let Some(Frame::Coroutine(state)) = k.pop() {
+                    *state.borrow_mut() = ast::CoroutineState::Suspended(ast::Captured(segment));
+                }
+                Step::Return(value)
+            }
+            Frame::Contracts { env } => match value {
+                ast::NylispExpression::Boolean(on) => {
+                    env.set_contract_checking(on);
+                    Step::Return(value)
+                }
+                _ => Step::Raise(ast::NylispError::of_kind("type", format!("🦺 requires a boolean, got {}", value))),
+            },
+            Frame::Force => match value {
+                ast::NylispExpression::Promise(promise) => self.force(promise, k),
+                _ => Step::Return(value),
+            },
+            Frame::Memo(promise) => {
+                // forcing itself may have forced the promise already, the first value wins
+                let mut state = promise.borrow_mut();
+                if let ast::Lazy::Forced(first) = &*state {
+                    return Step::Return(first.clone());
+                }
+                *state = ast::Lazy::Forced(value.clone());
+                Step::Return(value)
+            }
+            Frame::CallCc => {
+                let current = ast::NylispExpression::Continuation(ast::Captured(k.clone()));
+                self.apply(value.clone(), vec![current], &value, k)
+            }
+        }
+    }
+
+    // drop frames until a 🥅 takes the error, named closures on the way add themselves to the trace
+    fn unwind(&self, e: ast::NylispError, k: &mut Continuation) -> Result<Step, ast::NylispError> {
+        let mut e = e;
+        while let Some(frame) = k.pop() {
+            match frame {
+                Frame::Call(Some(name)) => e = ast::NylispError::InFunction(name, Box::new(e)),
+                Frame::Coroutine(state) => *state.borrow_mut() = ast::CoroutineState::Dead,
+                Frame::Try { catch: Some((name, handler)), finally, env } => {
+                    if let Some(exps) = finally {
+                        k.push(Frame::Finally { exps, env: env.clone() });
+                    }
+                    let mut data: std::collections::HashMap<ast::Symbol, ast::NylispExpression> = std::collections::HashMap::new();
+                    data.insert(name, e.to_expression());
+                    return Ok(self.eval_sequence(handler, 0, env.extend(data), k));
+                }
+                Frame::Try { catch: None, finally: Some(exps), env } | Frame::Finally { exps, env } => {
+                    k.push(Frame::Reraise(e));
+                    return Ok(self.eval_sequence(exps, 0, env, k));
+                }
+                _ => {}
+            }
+        }
+
+        Err(e)
+    }
+
+    // evaluates whatever is left of the operator and arguments, then applies
+    fn call(&self, exps: Rc<Vec<ast::NylispExpression>>, mut done: Vec<ast::NylispExpression>, env: ast::Environment, k: &mut Continuation) -> Step {
+        if done.len() == exps.len() {
+            let f = done.remove(0);
+            return self.apply(f, done, &exps[0], k);
+        }
+        let exp = exps[done.len()].clone();
+        k.push(Frame::Args { exps, done, env: env.clone() });
+        Step::Eval(exp, env)
+    }
+
+    fn apply(&self, f: ast::NylispExpression, mut args: Vec<ast::NylispExpression>, operator: &ast::NylispExpression, k: &mut Continuation) -> Step {
+        match f {
+            ast::NylispExpression::Function(f) => match f(args) {
+                Ok(value) => Step::Return(value),
+                Err(e) => Step::Raise(e),
+            },
+            ast::NylispExpression::Closure { args: params, body, env: cl_env, name, .. } => {
+                let (frame, mut defaults) = match self.new_closure_env(params, args, &cl_env, name, k.caller()) {
+                    Ok(bound) => bound,
+                    Err(e) => return Step::Raise(e),
+                };
+                // a tail call back into the same function reuses its frame, so loops don't grow the trace
+                if !matches!(k.peek(), Some(Frame::Call(current)) if *current == name) {
+                    k.push(Frame::Call(name));
+                }
+                if defaults.is_empty() {
+                    return Step::Eval((*body).clone(), frame);
+                }
+                defaults.push((*body).clone());
+                self.eval_sequence(Rc::new(defaults), 0, frame, k)
+            }
+            ast::NylispExpression::Keyword(key) => match self.keyword_lookup(key, args) {
+                Ok(value) => Step::Return(value),
+                Err(e) => Step::Raise(e),
+            },
+            ast::NylispExpression::Continuation(ast::Captured(continuation)) => {
+                // throw away the current frames and carry on from where 🔖 was called
+                if args.len() != 1 {
+                    return Step::Raise(ast::NylispError::of_kind("arity", format!("{} continuation takes exactly 1 argument, got {}", tokenizer::tokenizer::CALLCC, args.len())));
+                }
+                *k = continuation;
+                Step::Return(args.remove(0))
+            }
+            ast::NylispExpression::DelimitedContinuation(ast::Captured(segment)) => {
+                // run the captured frames on top of the current ones, inside a 🚧 of their own
+                if args.len() != 1 {
+                    return Step::Raise(ast::NylispError::of_kind("arity", format!("{} continuation takes exactly 1 argument, got {}", tokenizer::tokenizer::SHIFT, args.len())));
+                }
+                k.push(Frame::Reset);
+                for frame in segment.frames().into_iter().rev() {
+                    k.push(frame);
+                }
+                Step::Return(args.remove(0))
+            }
+            ast::NylispExpression::Coroutine(state) => self.resume_coroutine(state, args, k),
+            _ => Step::Raise(ast::NylispError::Because(format!("not a function: {:?}", operator)))
+        }
+    }
+
+    // a fresh coroutine calls its function with args, a suspended one gets its frames back and 🫳 returns the argument
+    fn resume_coroutine(&self, state: Rc<std::cell::RefCell<ast::CoroutineState>>, mut args: Vec<ast::NylispExpression>, k: &mut Continuation) -> Step {
+        let current = std::mem::replace(&mut *state.borrow_mut(), ast::CoroutineState::Running);
+        match current {
+            ast::CoroutineState::Fresh(f) => {
+                k.push(Frame::Coroutine(state));
+                self.apply(f.clone(), args, &f, k)
+            }
+            ast::CoroutineState::Suspended(ast::Captured(segment)) => {
+                if args.len() > 1 {
+                    *state.borrow_mut() = ast::CoroutineState::Suspended(ast::Captured(segment));
+                    return Step::Raise(ast::NylispError::of_kind("arity", format!("{} of a suspended coroutine takes at most 1 argument, got {}", tokenizer::tokenizer::RESUME, args.len())));
+                }
+                k.push(Frame::Coroutine(state));
+                for frame in segment.frames().into_iter().rev() {
+                    k.push(frame);
                 }
+                Step::Return(args.pop().unwrap_or(ast::NylispExpression::Nil))
+            }
+            other => {
+                let status = other.status();
+                *state.borrow_mut() = other;
+                Step::Raise(ast::NylispError::of_kind("coroutine", format!("{} can't resume a {} coroutine", tokenizer::tokenizer::RESUME, status)))
             }
-            _ => Err(ast::NylispError::Because(format!("unsupported expression type: {:?}", exp)))
         }
     }
 
-    fn wait_a_minute_is_this_a_special_form(&self, exp: ast::NylispExpression, args: Vec<ast::NylispExpression>, env: &mut ast::Environment) -> Result<Option<ast::NylispExpression>, ast::NylispError> {
+    fn wait_a_minute_is_this_a_special_form(&self, exp: &ast::NylispExpression, args: &[ast::NylispExpression], env: &ast::Environment, k: &mut Continuation) -> Result<Option<Step>, ast::NylispError> {
         match exp {
             ast::NylispExpression::Symbol(s) => {
                 match s.as_str() {
                     tokenizer::tokenizer::IF => {
-                        if args.len() != 3 {
-                            return Err(ast::NylispError::Because(format!("🐶 requires 3 arguments, got {}", args.len())))
-                        }
-                        let condition: ast::NylispExpression = self.evaluate(&args[0], env)?;
-                        let then_branch: ast::NylispExpression = self.evaluate(&args[1], env)?;
-                        let else_branch: ast::NylispExpression = self.evaluate(&args[2], env)?;
-                        match condition {
-                            ast::NylispExpression::Boolean(b) => {
-                                if b {
-                                     Ok(Some(then_branch))
-                                } else {
-                                    Ok(Some(else_branch))
+                        if args.len() != 2 && args.len() != 3 {
+                            return Err(ast::NylispError::Because(format!("🐶 requires 2 or 3 arguments, got {}", args.len())))
+                        }
+                        // only the chosen branch is evaluated, a missing else branch gives 🫥
+                        k.push(Frame::If {
+                            then: args[1].clone(),
+                            otherwise: args.get(2).cloned().unwrap_or(ast::NylispExpression::Nil),
+                            env: env.clone(),
+                        });
+                        Ok(Some(Step::Eval(args[0].clone(), env.clone())))
+                    }
+                    tokenizer::tokenizer::COND => {
+                        // first clause whose test is truthy wins, 🤷 always matches
+                        Ok(Some(self.cond(Rc::new(args.to_vec()), 0, env.clone(), k)))
+                    }
+                    tokenizer::tokenizer::CASE => {
+                        if args.is_empty() {
+                            return Err(ast::NylispError::Because("🔀 requires a key to dispatch on".to_string()))
+                        }
+                        k.push(Frame::Case { clauses: Rc::new(args[1..].to_vec()), env: env.clone() });
+                        Ok(Some(Step::Eval(args[0].clone(), env.clone())))
+                    }
+                    tokenizer::tokenizer::AND | tokenizer::tokenizer::OR => {
+                        // stops at the deciding value, otherwise gives back the last one
+                        let and = s.as_str() == tokenizer::tokenizer::AND;
+                        if args.is_empty() {
+                            return Ok(Some(Step::Return(ast::NylispExpression::Boolean(and))));
+                        }
+                        Ok(Some(self.logic(and, Rc::new(args.to_vec()), 0, env.clone(), k)))
+                    }
+                    tokenizer::tokenizer::RAISE => {
+                        // the arguments are evaluated like a call, then raise builds the error
+                        let mut exps = vec![exp.clone()];
+                        exps.extend(args.iter().cloned());
+                        Ok(Some(self.call(Rc::new(exps), vec![ast::NylispExpression::Function(raise)], env.clone(), k)))
+                    }
+                    tokenizer::tokenizer::TRY => {
+                        // 💖🥅 body... 💖🎣 e handler...💔 💖🧹 cleanup...💔💔, both clauses are optional
+                        let mut body: &[ast::NylispExpression] = args;
+                        let finally = match self.clause_of(tokenizer::tokenizer::FINALLY, body.last()) {
+                            Some(cleanup) => {
+                                body = &body[..body.len() - 1];
+                                Some(Rc::new(cleanup))
+                            }
+                            None => None,
+                        };
+                        let catch = match self.clause_of(tokenizer::tokenizer::CATCH, body.last()) {
+                            Some(handler) => {
+                                body = &body[..body.len() - 1];
+                                if handler.is_empty() {
+                                    return Err(ast::NylispError::Because("🎣 requires a symbol to bind the error to".to_string()))
                                 }
+                                Some((self.sym_from_symbol(handler[0].clone())?, Rc::new(handler[1..].to_vec())))
                             }
-                            _ => Err(ast::NylispError::Because(format!("🐶 requires a boolean condition, got {:?}", condition)))
+                            None => None,
+                        };
+
+                        k.push(Frame::Try { catch, finally, env: env.clone() });
+                        Ok(Some(self.eval_sequence(Rc::new(body.to_vec()), 0, env.clone(), k)))
+                    }
+                    tokenizer::tokenizer::WHEN | tokenizer::tokenizer::UNLESS => {
+                        if args.is_empty() {
+                            return Err(ast::NylispError::Because(format!("{} requires at least 1 argument, got 0", s)))
                         }
+                        let body = self.sequence(&args[1..]);
+                        let (then, otherwise) = if s.as_str() == tokenizer::tokenizer::WHEN {
+                            (body, ast::NylispExpression::Nil)
+                        } else {
+                            (ast::NylispExpression::Nil, body)
+                        };
+                        k.push(Frame::If { then, otherwise, env: env.clone() });
+                        Ok(Some(Step::Eval(args[0].clone(), env.clone())))
                     }
                     tokenizer::tokenizer::VAR => {
                         if args.len() != 2 {
                             return Err(ast::NylispError::Because(format!("🌷 requires 2 arguments, got {}", args.len())))
                         }
-                        let value: ast::NylispExpression = self.evaluate(&args[1], env)?;
-                        if let ast::NylispExpression::Symbol(s) = args[0].clone() {
-                            env.data.insert(s.clone(), value.clone());
-                            Ok(Some(ast::NylispExpression::List(vec![
-                                ast::NylispExpression::Symbol(s),
-                                value,
-                                ast::NylispExpression::Boolean(true),
-                            ])))
+                        if let ast::NylispExpression::Symbol(name) = args[0] {
+                            k.push(Frame::Define { name, env: env.clone() });
+                            Ok(Some(Step::Eval(args[1].clone(), env.clone())))
                         } else {
-                            return Err(ast::NylispError::Because(format!("🌷 requires a symbol as first argument, got {:?}", args[0])))
+                            Err(ast::NylispError::Because(format!("🌷 requires a symbol as first argument, got {:?}", args[0])))
+                        }
+                    }
+                    tokenizer::tokenizer::SET => {
+                        // assignment, changes the binding where it lives instead of shadowing it
+                        if args.len() != 2 {
+                            return Err(ast::NylispError::Because(format!("📝 requires 2 arguments, got {}", args.len())))
                         }
+                        let name = self.sym_from_symbol(args[0].clone())?;
+                        k.push(Frame::Set { name, env: env.clone() });
+                        Ok(Some(Step::Eval(args[1].clone(), env.clone())))
                     }
                     tokenizer::tokenizer::CLOSURE => {
                         // closure
-                        if args.len() != 2 {
-                            return Err(ast::NylispError::Because(format!("🏨 requires 2 arguments, got {}", args.len())))
+                        if args.len() < 2 {
+                            return Err(ast::NylispError::Because(format!("🏨 requires at least 2 arguments, got {}", args.len())))
                         }
+                        self.closure_params(&args[0])?;
+                        let (contract, body) = self.split_contract(&args[1..])?;
+                        let body = self.checked_result(None, contract, self.sequence(body));
 
                         Ok(
                             Some(
-                                ast::NylispExpression::Closure {
+                                Step::Return(ast::NylispExpression::Closure {
                                     args: Rc::new(args[0].clone()),
-                                    body: Rc::new(args[1].clone()),
-                                }
+                                    body: Rc::new(body),
+                                    env: env.clone(),
+                                    name: None,
+                                    doc: None,
+                                })
                             )
                         )
                     }
+                    tokenizer::tokenizer::DEFUN | tokenizer::tokenizer::DEFMACRO => {
+                        // 💖🍳 name params 📐 contract? docstring? body...💔 binds a named closure, 🧙 the same as a macro
+                        // the docstring is a 💬 string literal, the reader gives it to us as a String
+                        if args.len() < 3 {
+                            return Err(ast::NylispError::Because(format!("{} requires at least 3 arguments, got {}", s, args.len())))
+                        }
+                        let name = self.sym_from_symbol(args[0].clone())?;
+                        self.closure_params(&args[1])?;
+                        let (contract, rest) = self.split_contract(&args[2..])?;
+                        // a lone string is the body, not a docstring
+                        let (doc, body) = match rest.first() {
+                            Some(ast::NylispExpression::String(doc)) if rest.len() > 1 => (Some(doc.clone()), &rest[1..]),
+                            _ => (None, rest),
+                        };
+                        let closure = ast::NylispExpression::Closure {
+                            args: Rc::new(args[1].clone()),
+                            body: Rc::new(self.checked_result(Some(name), contract, self.sequence(body))),
+                            env: env.clone(),
+                            name: Some(name),
+                            doc,
+                        };
+                        if s.as_str() == tokenizer::tokenizer::DEFMACRO {
+                            env.define(name, ast::NylispExpression::Macro(Rc::new(closure)));
+                        } else {
+                            env.define(name, closure);
+                        }
+                        Ok(Some(Step::Return(ast::NylispExpression::Nil)))
+                    }
+                    tokenizer::tokenizer::MATCH => {
+                        // 💖🧩 value 💖pattern ✅ guard? body...💔...💔
+                        if args.is_empty() {
+                            return Err(ast::NylispError::Because("🧩 requires a value to match".to_string()))
+                        }
+                        k.push(Frame::Match { clauses: Rc::new(args[1..].to_vec()), env: env.clone() });
+                        Ok(Some(Step::Eval(args[0].clone(), env.clone())))
+                    }
+                    tokenizer::tokenizer::DEFINE_SYNTAX => {
+                        // the expander pass only binds 🧬 programs, one inside other code is bound where and when it runs
+                        let (name, syntax) = expander::expander::Expander::new(env).define_syntax(args)?;
+                        env.define(name, syntax);
+                        Ok(Some(Step::Return(ast::NylispExpression::Nil)))
+                    }
+                    tokenizer::tokenizer::RECORD => {
+                        // 💖📇 point 💖x y💔💔 defines point, point?, point-x and point-with-x for every field
+                        if args.len() != 2 {
+                            return Err(ast::NylispError::Because(format!("📇 requires 2 arguments, got {}", args.len())))
+                        }
+                        let name = self.sym_from_symbol(args[0].clone())?;
+                        let fields = match &args[1] {
+                            ast::NylispExpression::List(fields) => fields.iter().map(|f| self.sym_from_symbol(f.clone())).collect::<Result<Vec<_>, _>>()?,
+                            _ => return Err(ast::NylispError::Because(format!("📇 fields should be a list, but got {:?}", args[1]))),
+                        };
+                        if let Some(field) = fields.iter().enumerate().find(|(i, f)| fields[..*i].contains(f)).map(|(_, f)| f) {
+                            return Err(ast::NylispError::Because(format!("📇 {} has the field {} twice", name, field)));
+                        }
+                        for (procedure, params, body) in self.record_procedures(name, &fields) {
+                            env.define(procedure, ast::NylispExpression::Closure {
+                                args: Rc::new(ast::NylispExpression::List(params.into_iter().map(ast::NylispExpression::Symbol).collect())),
+                                body: Rc::new(ast::NylispExpression::List(body)),
+                                env: env.clone(),
+                                name: Some(procedure),
+                                doc: None,
+                            });
+                        }
+                        Ok(Some(Step::Return(ast::NylispExpression::Nil)))
+                    }
+                    tokenizer::tokenizer::MACROEXPAND => {
+                        // 💖🔍 😪💖m a💔💔 gives the code m expands to, without running it
+                        if args.len() != 1 {
+                            return Err(ast::NylispError::Because(format!("🔍 requires 1 argument, got {}", args.len())))
+                        }
+                        k.push(Frame::MacroExpand { env: env.clone() });
+                        Ok(Some(Step::Eval(args[0].clone(), env.clone())))
+                    }
+                    tokenizer::tokenizer::QUASIQUOTE => {
+                        if args.len() != 1 {
+                            return Err(ast::NylispError::Because(format!("🥱 requires 1 argument, got {}", args.len())))
+                        }
+                        Ok(Some(Step::Eval(self.quasiquote(&args[0], 1)?, env.clone())))
+                    }
+                    tokenizer::tokenizer::UNQUOTE | tokenizer::tokenizer::UNQUOTE_SPLICING => {
+                        Err(ast::NylispError::Because(format!("{} can only be used inside {}", s, tokenizer::tokenizer::QUASIQUOTE)))
+                    }
+                    tokenizer::tokenizer::BEGIN => {
+                        // evaluate in order, the last value is the result
+                        Ok(Some(self.eval_sequence(Rc::new(args.to_vec()), 0, env.clone(), k)))
+                    }
                     tokenizer::tokenizer::SCOPED_LET => {
                         // closure
-                        if args.len() != 2 {
-                            return Err(ast::NylispError::Because(format!("🍙 requires 2 arguments, got {}", args.len())))
+                        if args.len() < 2 {
+                            return Err(ast::NylispError::Because(format!("🍙 requires at least 2 arguments, got {}", args.len())))
+                        }
+
+                        let bindings = self.let_bindings(tokenizer::tokenizer::SCOPED_LET, &args[0])?;
+                        Ok(Some(self.let_call(exp, None, bindings, &args[1..], env, k)))
+                    }
+                    tokenizer::tokenizer::SEQUENTIAL_LET => {
+                        // let*, every binding sees the ones before it
+                        if args.len() < 2 {
+                            return Err(ast::NylispError::Because(format!("🍢 requires at least 2 arguments, got {}", args.len())))
+                        }
+
+                        // peel off the first binding and nest a 🍢 with the rest inside it
+                        let mut bindings = self.let_bindings(tokenizer::tokenizer::SEQUENTIAL_LET, &args[0])?;
+                        if bindings.len() <= 1 {
+                            return Ok(Some(self.let_call(exp, None, bindings, &args[1..], env, k)));
+                        }
+                        let first = bindings.remove(0);
+                        let rest = bindings.into_iter().map(|(pattern, var_exp)| ast::NylispExpression::List(vec![pattern, var_exp])).collect();
+                        let mut inner = vec![exp.clone(), ast::NylispExpression::List(rest)];
+                        inner.extend(args[1..].iter().cloned());
+                        Ok(Some(self.let_call(exp, None, vec![first], &[ast::NylispExpression::List(inner)], env, k)))
+                    }
+                    tokenizer::tokenizer::RECURSIVE_LET => {
+                        // letrec, every binding sees all of them so local functions can call each other
+                        if args.len() < 2 {
+                            return Err(ast::NylispError::Because(format!("🍥 requires at least 2 arguments, got {}", args.len())))
+                        }
+
+                        // every name is bound before the values exist, so there is nothing to destructure
+                        let bindings = self.let_bindings(tokenizer::tokenizer::RECURSIVE_LET, &args[0])?
+                            .into_iter()
+                            .map(|(var_name, var_exp)| Ok((self.sym_from_symbol(var_name)?, var_exp)))
+                            .collect::<Result<Vec<_>, ast::NylispError>>()?;
+                        let scope = env.extend(bindings.iter().map(|(var_name, _)| (*var_name, ast::NylispExpression::Nil)).collect());
+                        let mut exps: Vec<ast::NylispExpression> = bindings.into_iter().map(|(var_name, var_exp)| ast::NylispExpression::List(vec![
+                            ast::NylispExpression::Symbol(tokenizer::tokenizer::VAR.into()),
+                            ast::NylispExpression::Symbol(var_name),
+                            var_exp,
+                        ])).collect();
+                        exps.extend(args[1..].iter().cloned());
+                        Ok(Some(self.eval_sequence(Rc::new(exps), 0, scope, k)))
+                    }
+                    tokenizer::tokenizer::NAMED_LET => {
+                        // named let, the body can call name to loop with new values
+                        if args.len() < 3 {
+                            return Err(ast::NylispError::Because(format!("🔁 requires at least 3 arguments, got {}", args.len())))
                         }
 
-                        match self.evaluate(&args[1], &mut self.new_scoped_let_env(Rc::new(args[0].clone()), env).unwrap()) {
-                            Ok(evaluated_exp) => {
-                                Ok(Some(evaluated_exp))
+                        let name = self.sym_from_symbol(args[0].clone())?;
+                        let bindings = self.let_bindings(tokenizer::tokenizer::NAMED_LET, &args[1])?;
+                        Ok(Some(self.let_call(exp, Some(name), bindings, &args[2..], env, k)))
+                    }
+                    tokenizer::tokenizer::DELAY | tokenizer::tokenizer::STREAM_CONS => {
+                        // 💖💤 exp💔 is a promise to evaluate exp, 💖🌊 head tail💔 a stream cell made the same way
+                        let exp = match (s.as_str(), args) {
+                            (tokenizer::tokenizer::DELAY, [exp]) => exp.clone(),
+                            (tokenizer::tokenizer::STREAM_CONS, [head, tail]) => ast::NylispExpression::List(vec![
+                                ast::NylispExpression::Function(stream_cell),
+                                head.clone(),
+                                tail.clone(),
+                            ]),
+                            (_, _) => {
+                                let wanted = if s.as_str() == tokenizer::tokenizer::DELAY { 1 } else { 2 };
+                                return Err(ast::NylispError::Because(format!("{} requires {} arguments, got {}", s, wanted, args.len())))
+                            }
+                        };
+                        let promise = ast::Lazy::Delayed { exp, env: env.clone() };
+                        Ok(Some(Step::Return(ast::NylispExpression::Promise(Rc::new(std::cell::RefCell::new(promise))))))
+                    }
+                    tokenizer::tokenizer::CONTRACTS => {
+                        // 💖🦺💔 tells whether 📐 contracts are checked here, 💖🦺 👎💔 stops checking them
+                        match args.len() {
+                            0 => Ok(Some(Step::Return(ast::NylispExpression::Boolean(env.contract_checking())))),
+                            1 => {
+                                k.push(Frame::Contracts { env: env.clone() });
+                                Ok(Some(Step::Eval(args[0].clone(), env.clone())))
                             }
-                            Err(e) => Err(e)
+                            _ => Err(ast::NylispError::Because(format!("🦺 requires no arguments or a boolean, got {}", args.len()))),
+                        }
+                    }
+                    tokenizer::tokenizer::FORCE => {
+                        // anything that isn't a promise is already forced
+                        if args.len() != 1 {
+                            return Err(ast::NylispError::Because(format!("⏰ requires 1 argument, got {}", args.len())))
+                        }
+                        k.push(Frame::Force);
+                        Ok(Some(Step::Eval(args[0].clone(), env.clone())))
+                    }
+                    tokenizer::tokenizer::RESUME => {
+                        // 💖🎬 co value💔 is the same as calling 💖co value💔
+                        if args.is_empty() {
+                            return Err(ast::NylispError::Because("🎬 requires a coroutine to resume".to_string()))
+                        }
+                        Ok(Some(self.call(Rc::new(args.to_vec()), vec![], env.clone(), k)))
+                    }
+                    tokenizer::tokenizer::YIELD => {
+                        // 💖🫳 value💔 suspends the coroutine it runs in, the 🎬 that resumed it returns value
+                        if args.len() > 1 {
+                            return Err(ast::NylispError::Because(format!("🫳 requires 0 or 1 arguments, got {}", args.len())))
+                        }
+                        k.push(Frame::Yield);
+                        Ok(Some(Step::Eval(args.first().cloned().unwrap_or(ast::NylispExpression::Nil), env.clone())))
+                    }
+                    tokenizer::tokenizer::IMPORT => {
+                        // 💖📥 name 🔑only 💖a b💔 🔑rename 💖💖a x💔💔 🔑prefix m-💔, every option can be left out.
+                        // 🔑as m only makes m/a name a, unless 🔑only says which names to bring in as well
+                        let name = match args.first() {
+                            Some(ast::NylispExpression::Symbol(name)) => name.to_string(),
+                            Some(ast::NylispExpression::String(name)) => name.clone(),
+                            _ => return Err(ast::NylispError::Because(format!("📥 requires a module name, got {:?}", args.first()))),
+                        };
+                        let imported = module::module::import(env, &name)?;
+                        let (alias, names) = self.import_names(&name, &imported.exports, &args[1..])?;
+                        if let Some(alias) = alias {
+                            env.modules.borrow_mut().alias(alias.as_str(), &name);
                         }
+                        for (local, name) in names {
+                            env.define(local, ast::get_symbol(name, &imported.env).unwrap_or(ast::NylispExpression::Nil));
+                        }
+                        Ok(Some(Step::Return(ast::NylispExpression::Nil)))
+                    }
+                    tokenizer::tokenizer::EXPORT => {
+                        // 💖📤 a b💔 makes a and b importable from the module this runs in
+                        let names = args.iter().map(|name| self.sym_from_symbol(name.clone())).collect::<Result<Vec<_>, _>>()?;
+                        env.modules.borrow_mut().export(&names)?;
+                        Ok(Some(Step::Return(ast::NylispExpression::Nil)))
+                    }
+                    tokenizer::tokenizer::EXPORTS => {
+                        // 💖📋 m💔 lists what module m, or the module aliased m, exports
+                        let namespace = match args {
+                            [ast::NylispExpression::Symbol(namespace)] => namespace.to_string(),
+                            [ast::NylispExpression::String(namespace)] => namespace.clone(),
+                            _ => return Err(ast::NylispError::Because(format!("📋 requires a module name, got {} arguments", args.len()))),
+                        };
+                        match env.modules.borrow().public_bindings(&namespace) {
+                            Some(names) => Ok(Some(Step::Return(ast::NylispExpression::List(names.into_iter().map(ast::NylispExpression::Symbol).collect())))),
+                            None => Err(ast::NylispError::of_kind("module", format!("📋 no module {} is loaded", namespace))),
+                        }
+                    }
+                    tokenizer::tokenizer::CALLCC => {
+                        // 💖🔖 f💔 calls f with the rest of the computation as a function of one argument
+                        if args.len() != 1 {
+                            return Err(ast::NylispError::Because(format!("🔖 requires 1 argument, got {}", args.len())))
+                        }
+                        k.push(Frame::CallCc);
+                        Ok(Some(Step::Eval(args[0].clone(), env.clone())))
+                    }
+                    tokenizer::tokenizer::RESET => {
+                        // 💖🚧 body...💔 marks how far a 🪝 inside body can capture
+                        k.push(Frame::Reset);
+                        Ok(Some(self.eval_sequence(Rc::new(args.to_vec()), 0, env.clone(), k)))
+                    }
+                    tokenizer::tokenizer::SHIFT => {
+                        // 💖🪝 k body...💔 takes the computation up to the enclosing 🚧 out as k,
+                        // body's value is what the 🚧 returns
+                        if args.len() < 2 {
+                            return Err(ast::NylispError::Because(format!("🪝 requires at least 2 arguments, got {}", args.len())))
+                        }
+                        let name = self.sym_from_symbol(args[0].clone())?;
+                        let segment = match k.split_at(|frame| matches!(frame, Frame::Reset)) {
+                            Some(segment) => segment,
+                            None => return Err(ast::NylispError::Because("🪝 can only be used inside 🚧".to_string())),
+                        };
+                        let mut data: std::collections::HashMap<ast::Symbol, ast::NylispExpression> = std::collections::HashMap::new();
+                        data.insert(name, ast::NylispExpression::DelimitedContinuation(ast::Captured(segment)));
+                        Ok(Some(self.eval_sequence(Rc::new(args[1..].to_vec()), 0, env.extend(data), k)))
                     }
                     _ => Ok(None)
                 }
@@ -161,128 +802,884 @@ impl Evaluator {
         }
     }
 
-    fn new_closure_env<'a>(&self, param: Rc<ast::NylispExpression>, arg: Vec<ast::NylispExpression>, env: &'a mut ast::Environment) -> Result<ast::Environment<'a>, ast::NylispError> {
-        let param_strings = self.strs_from_list_of_symbols((*param).clone())?;
-        if param_strings.len() != arg.len() {
-            return Err(ast::NylispError::Because(format!("🏨 requires the same number of arguments as parameters, got {} and {}", param_strings.len(), arg.len())));
-        }
-        let evaled_args = arg.iter().map(|e| self.evaluate(e, env)).collect::<Result<Vec<ast::NylispExpression>, ast::NylispError>>()?;
-        let mut data: std::collections::HashMap<String, ast::NylispExpression> = std::collections::HashMap::new();
-        for (k, v) in param_strings.iter().zip(evaled_args.iter()) {
-            data.insert(k.clone(), v.clone());
+    // (name, parameters, body) of the closures a 📇 defines, the bodies call the record functions below directly
+    fn record_procedures(&self, name: ast::Symbol, fields: &[ast::Symbol]) -> Vec<(ast::Symbol, Vec<ast::Symbol>, Vec<ast::NylispExpression>)> {
+        let quoted = |s: ast::Symbol| ast::NylispExpression::Quote(Rc::new(ast::NylispExpression::Symbol(s)));
+        let record: ast::Symbol = "record".into();
+        let value: ast::Symbol = "value".into();
+
+        let mut constructor = vec![ast::NylispExpression::Function(record_new), quoted(name)];
+        constructor.push(ast::NylispExpression::Quote(Rc::new(ast::NylispExpression::List(fields.iter().map(|f| ast::NylispExpression::Symbol(*f)).collect()))));
+        constructor.extend(fields.iter().map(|f| ast::NylispExpression::Symbol(*f)));
+        let mut procedures = vec![
+            (name, fields.to_vec(), constructor),
+            (format!("{}?", name).into(), vec![value], vec![ast::NylispExpression::Function(record_is), quoted(name), ast::NylispExpression::Symbol(value)]),
+        ];
+        for field in fields {
+            procedures.push((format!("{}-{}", name, field).into(), vec![record], vec![
+                ast::NylispExpression::Function(record_get), quoted(name), quoted(*field), ast::NylispExpression::Symbol(record),
+            ]));
+            procedures.push((format!("{}-with-{}", name, field).into(), vec![record, value], vec![
+                ast::NylispExpression::Function(record_with), quoted(name), quoted(*field), ast::NylispExpression::Symbol(record), ast::NylispExpression::Symbol(value),
+            ]));
         }
-        Ok(ast::Environment {
-            _virtual: Some(env),
-            data,
-        })
+        procedures
     }
 
-    fn new_scoped_let_env<'a>(&self, variables: Rc<ast::NylispExpression>, env: &'a mut ast::Environment) -> Result<ast::Environment<'a>, ast::NylispError> {
-        // variables is something like ((a 1) (b 2))
-        let mut data: std::collections::HashMap<String, ast::NylispExpression> = std::collections::HashMap::new();
-        // check variables is a list
-        if let ast::NylispExpression::List(variable_list) = (*variables).clone() {
-            for var in variable_list {
-                // check var is a list
-                if let ast::NylispExpression::List(var_list) = var {
-                    if var_list.len() != 2 {
-                        return Err(ast::NylispError::Because(format!("🍙 requires a list of 2 elements, got {}", var_list.len())));
+    fn force(&self, promise: Rc<std::cell::RefCell<ast::Lazy>>, k: &mut Continuation) -> Step {
+        let (exp, env) = match &*promise.borrow() {
+            ast::Lazy::Forced(value) => return Step::Return(value.clone()),
+            ast::Lazy::Delayed { exp, env } => (exp.clone(), env.clone()),
+        };
+        k.push(Frame::Memo(promise));
+        Step::Eval(exp, env)
+    }
+
+    // the 🔑as alias and (local name, exported name) for every binding a 📥 with these options brings in
+    fn import_names(&self, module: &str, exports: &[ast::Symbol], options: &[ast::NylispExpression]) -> Result<(Option<ast::Symbol>, Imports), ast::NylispError> {
+        let mut alias: Option<ast::Symbol> = None;
+        let mut only: Option<Vec<ast::Symbol>> = None;
+        let mut renames: Vec<(ast::Symbol, ast::Symbol)> = Vec::new();
+        let mut prefix = String::new();
+        for option in options.chunks(2) {
+            match (&option[0], option.get(1)) {
+                (ast::NylispExpression::Keyword(k), Some(ast::NylispExpression::List(names))) if k.as_str() == "only" => {
+                    only = Some(names.iter().map(|name| self.sym_from_symbol(name.clone())).collect::<Result<Vec<_>, _>>()?);
+                }
+                (ast::NylispExpression::Keyword(k), Some(ast::NylispExpression::List(pairs))) if k.as_str() == "rename" => {
+                    for pair in pairs {
+                        match pair {
+                            ast::NylispExpression::List(pair) if pair.len() == 2 => {
+                                renames.push((self.sym_from_symbol(pair[0].clone())?, self.sym_from_symbol(pair[1].clone())?));
+                            }
+                            _ => return Err(ast::NylispError::Because(format!("📥 🔑rename takes 💖name new-name💔 lists, got {}", pair))),
+                        }
                     }
-                    let var_name = self.str_from_symbol(var_list[0].clone())?;
-                    let var_value = self.evaluate(&var_list[1], env)?;
-                    data.insert(var_name, var_value);
-                } else {
-                    return Err(ast::NylispError::Because(format!("🍙 requires a list of 2 elements, got {:?}", var)));
                 }
+                (ast::NylispExpression::Keyword(k), Some(ast::NylispExpression::Symbol(p))) if k.as_str() == "prefix" => prefix = p.to_string(),
+                (ast::NylispExpression::Keyword(k), Some(ast::NylispExpression::Symbol(a))) if k.as_str() == "as" => alias = Some(*a),
+                _ => return Err(ast::NylispError::Because(format!("📥 options are 🔑only, 🔑rename, 🔑prefix and 🔑as, got {}", option[0]))),
+            }
+        }
+
+        let names = match (only, alias) {
+            (Some(only), _) => only,
+            (None, Some(_)) => renames.iter().map(|(name, _)| *name).collect(),
+            (None, None) => exports.to_vec(),
+        };
+        let mut imports = Vec::new();
+        for name in names.iter().chain(renames.iter().map(|(name, _)| name)) {
+            if !exports.contains(name) {
+                return Err(ast::NylispError::of_kind("module", format!("module {} doesn't export {}", module, name)));
             }
-        } else {
-            return Err(ast::NylispError::Because(format!("🍙 first element should be a list, but got {:?}", variables)));
         }
+        for name in names {
+            let local = match renames.iter().find(|(from, _)| *from == name) {
+                Some((_, to)) => *to,
+                None if prefix.is_empty() => name,
+                None => format!("{}{}", prefix, name).into(),
+            };
+            imports.push((local, name));
+        }
+        Ok((alias, imports))
+    }
 
-        Ok(ast::Environment {
-            _virtual: Some(env),
-            data,
-        })
+    // the let forms are calls to a closure over the binding names, values are evaluated outside it
+    fn let_call(&self, form: &ast::NylispExpression, name: Option<ast::Symbol>, bindings: Vec<(ast::NylispExpression, ast::NylispExpression)>, body: &[ast::NylispExpression], env: &ast::Environment, k: &mut Continuation) -> Step {
+        let (params, values): (Vec<ast::NylispExpression>, Vec<ast::NylispExpression>) = bindings.into_iter().unzip();
+        // a named let gets its own frame so the name is only visible inside
+        let scope = match name {
+            Some(_) => env.extend(std::collections::HashMap::new()),
+            None => env.clone(),
+        };
+        let closure = ast::NylispExpression::Closure {
+            args: Rc::new(ast::NylispExpression::List(params)),
+            body: Rc::new(self.sequence(body)),
+            env: scope.clone(),
+            name,
+            doc: None,
+        };
+        if let Some(name) = name {
+            scope.define(name, closure.clone());
+        }
+        let mut exps = vec![form.clone()];
+        exps.extend(values);
+        self.call(Rc::new(exps), vec![closure], env.clone(), k)
     }
 
-    fn strs_from_list_of_symbols(&self, list: ast::NylispExpression) -> Result<Vec<String>, ast::NylispError> {
-        match list {
-            ast::NylispExpression::List(list) => {
-                let mut strings = vec![];
-                for item in &list {
-                    match item {
-                        ast::NylispExpression::Symbol(s) => {
-                            strings.push(s.clone());
-                        }
-                        _ => return Err(ast::NylispError::Because(format!("expected symbol, but got {:?}", list)))
-                    }
+    // the transformer of the macro named by head, or its 🧬 rules, if it names one
+    fn macro_for(&self, head: &ast::NylispExpression, env: &ast::Environment) -> Option<ast::NylispExpression> {
+        match head {
+            ast::NylispExpression::Symbol(s) => match ast::get_symbol(*s, env) {
+                Some(ast::NylispExpression::Macro(transformer)) => Some((*transformer).clone()),
+                Some(syntax @ ast::NylispExpression::SyntaxRules { .. }) => Some(syntax),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    // turns a 🥱 template into code that builds it: 😳 parts are evaluated, 🤯 parts are spliced into the list
+    // around them, and anything inside a nested 🥱 waits for one more level of 😳
+    fn quasiquote(&self, template: &ast::NylispExpression, depth: usize) -> Result<ast::NylispExpression, ast::NylispError> {
+        if !self.has_unquote(template) {
+            return Ok(ast::NylispExpression::Quote(Rc::new(template.clone())));
+        }
+        if let ast::NylispExpression::Quote(inner) = template {
+            return Ok(ast::NylispExpression::List(vec![ast::NylispExpression::Function(quasi_quote), self.quasiquote(inner, depth)?]));
+        }
+        if let Some((mark, arg)) = self.quasi_form(template) {
+            let depth = match mark.as_str() {
+                tokenizer::tokenizer::UNQUOTE if depth == 1 => return Ok(arg),
+                tokenizer::tokenizer::UNQUOTE_SPLICING if depth == 1 => {
+                    return Err(ast::NylispError::Because(format!("{} can only be used inside a list", tokenizer::tokenizer::UNQUOTE_SPLICING)))
                 }
-                Ok(strings)
+                tokenizer::tokenizer::QUASIQUOTE => depth + 1,
+                _ => depth - 1,
+            };
+            return Ok(ast::NylispExpression::List(vec![
+                ast::NylispExpression::Function(quasi_list),
+                ast::NylispExpression::Quote(Rc::new(ast::NylispExpression::Symbol(mark))),
+                self.quasiquote(&arg, depth)?,
+            ]));
+        }
+
+        let (mut items, mut tail) = template.unroll().unwrap();
+        // 💖a 🔗 😳b💔 reads as 💖a 😳 b💔, the unquote is the tail
+        if items.len() >= 2 && matches!(&items[items.len() - 2], ast::NylispExpression::Symbol(s) if s.as_str() == tokenizer::tokenizer::UNQUOTE) && tail.is_none() {
+            let last = items.pop().unwrap();
+            tail = Some(ast::NylispExpression::List(vec![items.pop().unwrap(), last]));
+        }
+        let mut parts = vec![ast::NylispExpression::Function(quasi_append)];
+        for item in items.iter() {
+            match self.quasi_form(item) {
+                Some((mark, arg)) if depth == 1 && mark.as_str() == tokenizer::tokenizer::UNQUOTE_SPLICING => parts.push(arg),
+                _ => parts.push(ast::NylispExpression::List(vec![ast::NylispExpression::Function(quasi_list), self.quasiquote(item, depth)?])),
             }
-            _ => Err(ast::NylispError::Because(format!("expected list, but got {:?}", list)))
+        }
+        let list = ast::NylispExpression::List(parts);
+        match tail {
+            Some(tail) => Ok(ast::NylispExpression::List(vec![ast::NylispExpression::Function(quasi_dotted), list, self.quasiquote(&tail, depth)?])),
+            None => Ok(list),
         }
     }
 
-    fn str_from_symbol(&self, symbol: ast::NylispExpression) -> Result<String, ast::NylispError> {
-        match symbol {
-            ast::NylispExpression::Symbol(s) => Ok(s),
-            _ => return Err(ast::NylispError::Because(format!("expected symbol, but got {:?}", symbol)))
+    fn has_unquote(&self, template: &ast::NylispExpression) -> bool {
+        match template {
+            ast::NylispExpression::Symbol(s) => s.as_str() == tokenizer::tokenizer::UNQUOTE || s.as_str() == tokenizer::tokenizer::UNQUOTE_SPLICING,
+            ast::NylispExpression::Quote(inner) => self.has_unquote(inner),
+            ast::NylispExpression::List(_) | ast::NylispExpression::Pair(_, _) | ast::NylispExpression::Tail(_) => match template.unroll() {
+                Some((items, tail)) => items.iter().chain(tail.iter()).any(|item| self.has_unquote(item)),
+                None => false,
+            },
+            _ => false,
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::environment;
-    use super::*;
+    // 💖🥱 x💔, 💖😳 x💔 or 💖🤯 x💔 split into the mark and x
+    fn quasi_form(&self, exp: &ast::NylispExpression) -> Option<(ast::Symbol, ast::NylispExpression)> {
+        match exp {
+            ast::NylispExpression::List(l) if l.len() == 2 => match &l[0] {
+                ast::NylispExpression::Symbol(s) if [tokenizer::tokenizer::QUASIQUOTE, tokenizer::tokenizer::UNQUOTE, tokenizer::tokenizer::UNQUOTE_SPLICING].contains(&s.as_str()) => {
+                    Some((*s, l[1].clone()))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
 
-    fn input_and_go(input: &str) -> Vec<Result<ast::NylispExpression, ast::NylispError>> {
-        let mut tokenizer_obj = tokenizer::tokenizer::Tokenizer::new(input.to_string());
-        let tokens = tokenizer_obj.tokenize();
-        let mut parser = parser::parser::Parser::new();
-        let (result, _) = parser.parse_program(tokens).unwrap();
-        let mut evaluator = Evaluator::new(vec![result]);
-        let result = evaluator.eval_programs(&mut ast::Environment::from(environment::environment::builtin_env()));
+    // runs the first clause from clauses[next..] whose pattern matches and whose guard, if any, passes
+    fn match_clauses(&self, subject: ast::NylispExpression, clauses: Rc<Vec<ast::NylispExpression>>, next: usize, env: ast::Environment, k: &mut Continuation) -> Step {
+        for i in next..clauses.len() {
+            let clause = match self.clause(tokenizer::tokenizer::MATCH, &clauses[i]) {
+                Ok(clause) => clause,
+                Err(e) => return Step::Raise(e),
+            };
+            let mut bindings: std::collections::HashMap<ast::Symbol, ast::NylispExpression> = std::collections::HashMap::new();
+            if !self.match_pattern(&clause[0], &subject, &mut bindings) {
+                continue;
+            }
+            let scope = env.extend(bindings);
+            let guarded = matches!(clause.get(1), Some(ast::NylispExpression::Symbol(s)) if s.as_str() == tokenizer::tokenizer::WHEN);
+            if !guarded {
+                return self.eval_sequence(Rc::new(clause[1..].to_vec()), 0, scope, k);
+            }
+            let guard = match clause.get(2) {
+                Some(guard) => guard.clone(),
+                None => return Step::Raise(ast::NylispError::Because(format!("🧩 requires an expression after {}", tokenizer::tokenizer::WHEN))),
+            };
+            k.push(Frame::Guard { subject, clauses, next: i, env, scope: scope.clone() });
+            return Step::Eval(guard, scope);
+        }
 
-        result
+        Step::Raise(ast::NylispError::of_kind("match", format!("🧩 no pattern matches {}", subject)))
     }
 
-    // +
-    #[test]
-    fn eval_nylisp_test_plus() {
-        let input = "💖+ 1 2💔";
-        let expected: Result<ast::NylispExpression, ast::NylispError> = Ok(ast::NylispExpression::Number(3.0));
-        let got = input_and_go(input);
-        assert_eq!(got[0], expected);
+    // 🤷 matches anything, symbols bind, 😪x matches x itself, lists match element by element with 🔗 for the rest.
+    // a symbol used twice has to match equal values. a 📇 record matches 💖type 🔗 field patterns💔 by position
+    fn match_pattern(&self, pattern: &ast::NylispExpression, value: &ast::NylispExpression, bindings: &mut std::collections::HashMap<ast::Symbol, ast::NylispExpression>) -> bool {
+        match pattern {
+            _ if self.is_else(pattern) => true,
+            ast::NylispExpression::Symbol(s) => match bindings.get(s) {
+                Some(bound) => bound == value,
+                None => {
+                    bindings.insert(*s, value.clone());
+                    true
+                }
+            },
+            ast::NylispExpression::Quote(q) => **q == *value,
+            ast::NylispExpression::List(_) | ast::NylispExpression::Pair(_, _) | ast::NylispExpression::Tail(_) if matches!(value, ast::NylispExpression::Record { .. }) => {
+                let (items, ast::NylispExpression::Record { name, fields }) = (pattern.proper_list().unwrap_or_default(), value) else {
+                    return false;
+                };
+                match items.split_first() {
+                    Some((ast::NylispExpression::Symbol(head), rest)) if head == name && rest.len() == fields.len() => {
+                        rest.iter().zip(fields.iter()).all(|(p, (_, v))| self.match_pattern(p, v, bindings))
+                    }
+                    _ => false,
+                }
+            }
+            ast::NylispExpression::List(_) | ast::NylispExpression::Pair(_, _) | ast::NylispExpression::Tail(_) => {
+                let ((p_items, p_tail), (v_items, v_tail)) = match (pattern.unroll(), value.unroll()) {
+                    (Some(p), Some(v)) => (p, v),
+                    _ => return false,
+                };
+                let wrong_length = p_tail.is_none() && (v_items.len() != p_items.len() || v_tail.is_some());
+                if wrong_length || v_items.len() < p_items.len() {
+                    return false;
+                }
+                for (p, v) in p_items.iter().zip(v_items.iter()) {
+                    if !self.match_pattern(p, v, bindings) {
+                        return false;
+                    }
+                }
+                match p_tail {
+                    Some(p_tail) => self.match_pattern(&p_tail, &rest_of(&v_items[p_items.len()..], v_tail), bindings),
+                    None => true,
+                }
+            }
+            _ => pattern == value,
+        }
     }
 
-    // -
-    #[test]
-    fn eval_nylisp_test_minus() {
-        let input = "💖- 1 2💔";
-        let expected: Result<ast::NylispExpression, ast::NylispError> = Ok(ast::NylispExpression::Number(-1.0));
-        let got = input_and_go(input);
-        assert_eq!(got[0], expected);
+    // binds the symbols of a 🐷 parameter or 🍙 binding pattern like 💖💖a b💔 🔗 rest💔 to the parts of value,
+    // unlike 🧩 a value of the wrong shape is an error
+    fn destructure(&self, pattern: &ast::NylispExpression, value: &ast::NylispExpression, bindings: &mut Vec<(ast::Symbol, ast::NylispExpression)>) -> Result<(), ast::NylispError> {
+        let (p_items, p_tail) = match pattern {
+            _ if self.is_else(pattern) => return Ok(()),
+            ast::NylispExpression::Symbol(s) => {
+                bindings.push((*s, value.clone()));
+                return Ok(());
+            }
+            _ => pattern.unroll().unwrap_or_default(),
+        };
+        let (v_items, v_tail) = match value.unroll() {
+            Some(unrolled) => unrolled,
+            None => return Err(ast::NylispError::of_kind("shape", format!("expected a list for {}, got {}", pattern, value))),
+        };
+        if p_tail.is_none() && (v_items.len() != p_items.len() || v_tail.is_some()) {
+            return Err(ast::NylispError::of_kind("shape", format!("expected a list of {} elements for {}, got {}", p_items.len(), pattern, value)));
+        }
+        if v_items.len() < p_items.len() {
+            return Err(ast::NylispError::of_kind("shape", format!("expected at least {} elements for {}, got {}", p_items.len(), pattern, value)));
+        }
+        for (p, v) in p_items.iter().zip(v_items.iter()) {
+            self.destructure(p, v, bindings)?;
+        }
+        match p_tail {
+            Some(p_tail) => self.destructure(&p_tail, &rest_of(&v_items[p_items.len()..], v_tail), bindings),
+            None => Ok(()),
+        }
     }
 
-    // <>
-    #[test]
-    fn eval_nylisp_test_bigger_or_smaller() {
-        let input = "💖> 1 2 3 4💔";
-        let expected = ast::NylispExpression::Boolean(false);
-        let got = input_and_go(input);
-        assert_eq!(got[0], Ok(expected));
+    // patterns are symbols and lists of patterns, possibly dotted
+    fn check_pattern(&self, pattern: &ast::NylispExpression) -> Result<(), ast::NylispError> {
+        match pattern {
+            ast::NylispExpression::Symbol(_) => Ok(()),
+            ast::NylispExpression::List(_) | ast::NylispExpression::Pair(_, _) | ast::NylispExpression::Tail(_) => {
+                let (items, tail) = pattern.unroll().unwrap_or_default();
+                items.iter().chain(tail.iter()).try_for_each(|p| self.check_pattern(p))
+            }
+            _ => Err(ast::NylispError::Because(format!("expected symbol, but got {:?}", pattern))),
+        }
+    }
 
-        let input = "💖< 1 2 3 4💔";
-        let expected = ast::NylispExpression::Boolean(true);
-        let got = input_and_go(input);
-        assert_eq!(got[0], Ok(expected));
+    fn logic(&self, and: bool, exps: Rc<Vec<ast::NylispExpression>>, next: usize, env: ast::Environment, k: &mut Continuation) -> Step {
+        let exp = exps[next].clone();
+        if next + 1 < exps.len() {
+            k.push(Frame::Logic { and, exps, next: next + 1, env: env.clone() });
+        }
+        Step::Eval(exp, env)
     }
 
-    // car and quote
-    #[test]
-    fn eval_nylisp_test_car_quote() {
-        let input = "💖🚗😪💖👎 👍 👍💔💔";
+    fn cond(&self, clauses: Rc<Vec<ast::NylispExpression>>, next: usize, env: ast::Environment, k: &mut Continuation) -> Step {
+        if next == clauses.len() {
+            return Step::Return(ast::NylispExpression::Nil);
+        }
+        let clause = match self.clause(tokenizer::tokenizer::COND, &clauses[next]) {
+            Ok(clause) => clause,
+            Err(e) => return Step::Raise(e),
+        };
+        if self.is_else(&clause[0]) {
+            return self.eval_sequence(Rc::new(clause[1..].to_vec()), 0, env, k);
+        }
+        k.push(Frame::Cond { clauses, next, env: env.clone() });
+        Step::Eval(clause[0].clone(), env)
+    }
+
+    // 💖🔑key map default💔 looks key up in an association list, giving default (or 🫥) when it is missing
+    fn keyword_lookup(&self, key: ast::Symbol, args: Vec<ast::NylispExpression>) -> Result<ast::NylispExpression, ast::NylispError> {
+        if args.is_empty() || args.len() > 2 {
+            return Err(ast::NylispError::Because(format!("🔑{} requires a map and an optional default, got {} arguments", key, args.len())));
+        }
+        if let ast::NylispExpression::Record { fields, .. } = &args[0] {
+            let found = fields.iter().find(|(field, _)| *field == key).map(|(_, v)| v.clone());
+            return Ok(found.or_else(|| args.get(1).cloned()).unwrap_or(ast::NylispExpression::Nil));
+        }
+        let entries = match args[0].proper_list() {
+            Some(entries) => entries,
+            None => return Err(ast::NylispError::Because(format!("🔑{} requires an association list, got {}", key, args[0]))),
+        };
+        // entries are dotted 💖k 🔗 v💔 pairs or two element 💖k v💔 lists, anything else is a mistake rather than a miss
+        for entry in entries {
+            let (k, v) = match &entry {
+                ast::NylispExpression::Pair(k, v) => ((**k).clone(), (**v).clone()),
+                _ => match entry.proper_list() {
+                    Some(kv) if kv.len() == 2 => {
+                        let mut kv = kv.into_iter();
+                        (kv.next().unwrap(), kv.next().unwrap())
+                    }
+                    _ => return Err(ast::NylispError::of_kind("type", format!("🔑{} association list entries should be 💖key value💔 or 💖key 🔗 value💔, got {}", key, entry))),
+                },
+            };
+            if k == ast::NylispExpression::Keyword(key) {
+                return Ok(v);
+            }
+        }
+
+        Ok(args.get(1).cloned().unwrap_or(ast::NylispExpression::Nil))
+    }
+
+    // binds evaluated arguments on top of the closure's own scope. defaults are evaluated in the new frame
+    // so they can use the parameters before them, that happens in the body: the returned 🌹 forms run first.
+    // caller is the closure making the call, blamed when an argument breaks its contract
+    fn new_closure_env(&self, param: Rc<ast::NylispExpression>, evaled_args: Vec<ast::NylispExpression>, closure_env: &ast::Environment, name: Option<ast::Symbol>, caller: Option<Option<ast::Symbol>>) -> Result<(ast::Environment, Vec<ast::NylispExpression>), ast::NylispError> {
+        let params = self.closure_params(&param)?;
+        let positional = params.required.len() + params.optional.len();
+        let too_few = evaled_args.len() < params.required.len();
+        let too_many = evaled_args.len() > positional && params.rest.is_none() && params.keys.is_empty();
+        if too_few || too_many {
+            return Err(ast::NylispError::of_kind("arity", format!("🐷 {} takes {}, got {}", param, params.arity(), evaled_args.len())));
+        }
+
+        let frame = closure_env.extend(std::collections::HashMap::new());
+        // once a default has to be evaluated, later parameters are bound after it so the order stays the same
+        let mut defaults: Vec<ast::NylispExpression> = Vec::new();
+        let mut bind = |name: ast::Symbol, value: Option<ast::NylispExpression>, default: &ast::NylispExpression| {
+            let value = match value {
+                Some(value) if defaults.is_empty() => return frame.define(name, value),
+                Some(value) => ast::NylispExpression::Quote(Rc::new(value)),
+                None => default.clone(),
+            };
+            defaults.push(ast::NylispExpression::List(vec![
+                ast::NylispExpression::Symbol(tokenizer::tokenizer::VAR.into()),
+                ast::NylispExpression::Symbol(name),
+                value,
+            ]));
+        };
+        let mut evaled_args = evaled_args.into_iter();
+        for pattern in params.required.iter() {
+            let value = evaled_args.next().unwrap_or(ast::NylispExpression::Nil);
+            let mut parts = Vec::new();
+            self.destructure(pattern, &value, &mut parts)?;
+            for (name, value) in parts {
+                bind(name, Some(value), &ast::NylispExpression::Nil);
+            }
+        }
+        for (name, default) in params.optional.iter() {
+            bind(*name, evaled_args.next(), default);
+        }
+        let extra: Vec<ast::NylispExpression> = evaled_args.collect();
+        if !params.keys.is_empty() {
+            let mut given: std::collections::HashMap<ast::Symbol, ast::NylispExpression> = std::collections::HashMap::new();
+            for pair in extra.chunks(2) {
+                let key = match &pair[0] {
+                    ast::NylispExpression::Keyword(k) if params.keys.iter().any(|(name, _)| name == k) => *k,
+                    _ => return Err(ast::NylispError::of_kind("arity", format!("🐷 {} takes {}, got an unexpected {}", param, params.arity(), pair[0]))),
+                };
+                match pair.get(1) {
+                    Some(value) => given.insert(key, value.clone()),
+                    None => return Err(ast::NylispError::Because(format!("🐷 {} got no value for {}{}", param, tokenizer::tokenizer::KEYWORD, key))),
+                };
+            }
+            for (name, default) in params.keys.iter() {
+                bind(*name, given.remove(name), default);
+            }
+        }
+        if let Some(rest) = params.rest {
+            bind(rest, Some(ast::NylispExpression::List(extra)), &ast::NylispExpression::Nil);
+        }
+        // checked after every parameter, defaults included, is bound
+        if closure_env.contract_checking() {
+            let caller = match caller {
+                Some(Some(caller)) => caller.to_string(),
+                Some(None) => "anonymous".to_string(),
+                None => "the top level".to_string(),
+            };
+            for (param, contract) in params.contracts {
+                let what = ast::NylispExpression::String(format!("argument {}", param));
+                let blame = format!("{}, which passed {},", caller, param);
+                defaults.push(self.contract_check(name, what, &contract, param, ast::NylispExpression::Nil, &blame));
+            }
+        }
+
+        Ok((frame, defaults))
+    }
+
+    // 💖a b ❓ 💖c 1💔 🔐 d 🔗 rest💔, a bare symbol collects every argument
+    fn closure_params(&self, param: &ast::NylispExpression) -> Result<ClosureParams, ast::NylispError> {
+        let mut params = ClosureParams { required: vec![], optional: vec![], keys: vec![], rest: None, contracts: vec![] };
+        let (items, tail) = match param {
+            ast::NylispExpression::Symbol(rest) => (vec![], Some(ast::NylispExpression::Symbol(*rest))),
+            _ => match param.unroll() {
+                Some(unrolled) => unrolled,
+                None => return Err(ast::NylispError::Because(format!("🐷 parameters should be a list, but got {:?}", param))),
+            },
+        };
+        if let Some(tail) = tail {
+            params.rest = Some(self.sym_from_symbol(tail)?);
+        }
+
+        let mut section = "";
+        let mut last: Option<ast::Symbol> = None;
+        let mut items = items.into_iter();
+        while let Some(item) = items.next() {
+            match &item {
+                ast::NylispExpression::Symbol(s) if s.as_str() == tokenizer::tokenizer::CONTRACT => {
+                    let (name, contract) = match (last.take(), items.next()) {
+                        (Some(name), Some(contract)) => (name, contract),
+                        _ => return Err(ast::NylispError::Because(format!("📐 has to come between a parameter name and its contract: {}", param))),
+                    };
+                    params.contracts.push((name, contract));
+                    continue;
+                }
+                ast::NylispExpression::Symbol(s) if s.as_str() == tokenizer::tokenizer::OPTIONAL || s.as_str() == tokenizer::tokenizer::KEYS => {
+                    if section == tokenizer::tokenizer::KEYS || section == s.as_str() {
+                        return Err(ast::NylispError::Because(format!("🐷 parameters can't have {} here: {}", s, param)));
+                    }
+                    section = s.as_str();
+                }
+                _ if section.is_empty() => {
+                    self.check_pattern(&item)?;
+                    params.required.push(item.clone());
+                    last = match item {
+                        ast::NylispExpression::Symbol(s) => Some(s),
+                        _ => None,
+                    };
+                    continue;
+                }
+                _ => {
+                    // name or 💖name default💔
+                    let (name, default) = match &item {
+                        ast::NylispExpression::Symbol(s) => (*s, ast::NylispExpression::Nil),
+                        ast::NylispExpression::List(l) if l.len() == 2 => (self.sym_from_symbol(l[0].clone())?, l[1].clone()),
+                        _ => return Err(ast::NylispError::Because(format!("{} parameters are a symbol or a list of 2 elements, got {:?}", section, item))),
+                    };
+                    if section == tokenizer::tokenizer::OPTIONAL {
+                        params.optional.push((name, default));
+                    } else {
+                        params.keys.push((name, default));
+                    }
+                    last = Some(name);
+                    continue;
+                }
+            }
+            last = None;
+        }
+
+        Ok(params)
+    }
+
+    // the 📐 contract in front of a closure body, if there is one, and the rest
+    fn split_contract<'a>(&self, body: &'a [ast::NylispExpression]) -> Result<(Option<&'a ast::NylispExpression>, &'a [ast::NylispExpression]), ast::NylispError> {
+        match body {
+            [ast::NylispExpression::Symbol(s), contract, rest @ ..] if s.as_str() == tokenizer::tokenizer::CONTRACT => {
+                if rest.is_empty() {
+                    return Err(ast::NylispError::Because(format!("📐 {} requires a body after it", contract)));
+                }
+                Ok((Some(contract), rest))
+            }
+            [ast::NylispExpression::Symbol(s)] if s.as_str() == tokenizer::tokenizer::CONTRACT => {
+                Err(ast::NylispError::Because("📐 requires a contract and a body after it".to_string()))
+            }
+            _ => Ok((None, body)),
+        }
+    }
+
+    // the body, followed by a check of its value when the closure promises a result contract
+    fn checked_result(&self, name: Option<ast::Symbol>, contract: Option<&ast::NylispExpression>, body: ast::NylispExpression) -> ast::NylispExpression {
+        let contract = match contract {
+            Some(contract) => contract,
+            None => return body,
+        };
+        let result = ast::Symbol::gensym("result");
+        let blame = name.map(|name| name.to_string()).unwrap_or_else(|| tokenizer::tokenizer::CLOSURE.to_string());
+        let check = self.contract_check(name, ast::NylispExpression::String("result".to_string()), contract, result, ast::NylispExpression::Symbol(result), &blame);
+        ast::NylispExpression::List(vec![
+            ast::NylispExpression::Symbol(tokenizer::tokenizer::SCOPED_LET.into()),
+            ast::NylispExpression::List(vec![ast::NylispExpression::List(vec![ast::NylispExpression::Symbol(result), body])]),
+            check,
+        ])
+    }
+
+    // 💖🐶 ok? passed 💖blame ...💔💔 where ok? is the contract applied to the value bound to param.
+    // checking can be switched off with 🦺 after the closure was made, so that is asked at run time too,
+    // 🦺 is a special form so the environment the check runs in answers
+    fn contract_check(&self, name: Option<ast::Symbol>, what: ast::NylispExpression, contract: &ast::NylispExpression, param: ast::Symbol, passed: ast::NylispExpression, blame: &str) -> ast::NylispExpression {
+        let value = ast::NylispExpression::Symbol(param);
+        let quoted = |exp: ast::NylispExpression| ast::NylispExpression::Quote(Rc::new(exp));
+        let ok = match contract {
+            ast::NylispExpression::Symbol(s) if named_type(s.as_str(), &ast::NylispExpression::Nil).is_some() => {
+                ast::NylispExpression::List(vec![ast::NylispExpression::Function(contract_type), quoted(contract.clone()), value.clone()])
+            }
+            _ => ast::NylispExpression::List(vec![contract.clone(), value.clone()]),
+        };
+        let ok = ast::NylispExpression::List(vec![
+            ast::NylispExpression::Symbol(tokenizer::tokenizer::IF.into()),
+            ast::NylispExpression::List(vec![ast::NylispExpression::Symbol(tokenizer::tokenizer::CONTRACTS.into())]),
+            ok,
+            ast::NylispExpression::Boolean(true),
+        ]);
+        let function = name.map(|name| name.to_string()).unwrap_or_else(|| tokenizer::tokenizer::CLOSURE.to_string());
+        ast::NylispExpression::List(vec![
+            ast::NylispExpression::Symbol(tokenizer::tokenizer::IF.into()),
+            ok,
+            passed,
+            ast::NylispExpression::List(vec![
+                ast::NylispExpression::Function(contract_blame),
+                ast::NylispExpression::String(function),
+                what,
+                quoted(contract.clone()),
+                value,
+                ast::NylispExpression::String(blame.to_string()),
+            ]),
+        ])
+    }
+
+    // runs exps[next..] in order, the last one in tail position
+    fn eval_sequence(&self, exps: Rc<Vec<ast::NylispExpression>>, next: usize, env: ast::Environment, k: &mut Continuation) -> Step {
+        if next >= exps.len() {
+            return Step::Return(ast::NylispExpression::Nil);
+        }
+        let exp = exps[next].clone();
+        if next + 1 < exps.len() {
+            k.push(Frame::Sequence { exps, next: next + 1, env: env.clone() });
+        }
+        Step::Eval(exp, env)
+    }
+
+    // a closure keeps a single body, several expressions get wrapped in 👣
+    fn sequence(&self, body: &[ast::NylispExpression]) -> ast::NylispExpression {
+        if body.len() == 1 {
+            return body[0].clone();
+        }
+        let mut exps = vec![ast::NylispExpression::Symbol(tokenizer::tokenizer::BEGIN.into())];
+        exps.extend(body.iter().cloned());
+        ast::NylispExpression::List(exps)
+    }
+
+    // clauses of 🚦 and 🔀 are non-empty lists
+    fn clause(&self, form: &str, clause: &ast::NylispExpression) -> Result<Vec<ast::NylispExpression>, ast::NylispError> {
+        match clause {
+            ast::NylispExpression::List(clause) if !clause.is_empty() => Ok(clause.clone()),
+            _ => Err(ast::NylispError::Because(format!("{} requires each clause to be a non-empty list, got {:?}", form, clause))),
+        }
+    }
+
+    // rest of a 💖🎣 ...💔 or 💖🧹 ...💔 clause at the end of 🥅
+    fn clause_of(&self, form: &str, exp: Option<&ast::NylispExpression>) -> Option<Vec<ast::NylispExpression>> {
+        match exp {
+            Some(ast::NylispExpression::List(l)) => match l.first() {
+                Some(ast::NylispExpression::Symbol(s)) if s.as_str() == form => Some(l[1..].to_vec()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn is_else(&self, exp: &ast::NylispExpression) -> bool {
+        matches!(exp, ast::NylispExpression::Symbol(s) if s.as_str() == tokenizer::tokenizer::ELSE)
+    }
+
+    // checks the binding list shared by the let forms and hands back (name or pattern, unevaluated value) pairs
+    fn let_bindings(&self, form: &str, variables: &ast::NylispExpression) -> Result<Vec<(ast::NylispExpression, ast::NylispExpression)>, ast::NylispError> {
+        // variables is something like ((a 1) ((b c) (😪 (2 3))))
+        let mut bindings: Vec<(ast::NylispExpression, ast::NylispExpression)> = Vec::new();
+        // check variables is a list
+        if let ast::NylispExpression::List(variable_list) = variables {
+            for var in variable_list {
+                // check var is a list
+                if let ast::NylispExpression::List(var_list) = var {
+                    if var_list.len() != 2 {
+                        return Err(ast::NylispError::Because(format!("{} requires a list of 2 elements, got {}", form, var_list.len())));
+                    }
+                    self.check_pattern(&var_list[0])?;
+                    bindings.push((var_list[0].clone(), var_list[1].clone()));
+                } else {
+                    return Err(ast::NylispError::Because(format!("{} requires a list of 2 elements, got {:?}", form, var)));
+                }
+            }
+        } else {
+            return Err(ast::NylispError::Because(format!("{} first element should be a list, but got {:?}", form, variables)));
+        }
+
+        Ok(bindings)
+    }
+
+    fn sym_from_symbol(&self, symbol: ast::NylispExpression) -> Result<ast::Symbol, ast::NylispError> {
+        match symbol {
+            ast::NylispExpression::Symbol(s) => Ok(s),
+            _ => Err(ast::NylispError::Because(format!("expected symbol, but got {:?}", symbol)))
+        }
+    }
+}
+
+// 💖🧨 payload💔 or 💖🧨 🔑kind 💬message💬 payload?💔, error objects are raised as they are
+fn raise(args: Vec<ast::NylispExpression>) -> Result<ast::NylispExpression, ast::NylispError> {
+    let error = match args.as_slice() {
+        [e @ ast::NylispExpression::Error { .. }] => e.clone(),
+        [payload] => ast::NylispExpression::Error {
+            kind: "raised".into(),
+            message: payload.to_string(),
+            payload: Rc::new(payload.clone()),
+        },
+        [ast::NylispExpression::Keyword(kind), ast::NylispExpression::String(message), payload @ ..] if payload.len() <= 1 => ast::NylispExpression::Error {
+            kind: *kind,
+            message: message.clone(),
+            payload: Rc::new(payload.first().cloned().unwrap_or(ast::NylispExpression::Nil)),
+        },
+        _ => return Err(ast::NylispError::Because(format!("🧨 requires a payload, or a keyword, a string and an optional payload, got {}", ast::NylispExpression::List(args)))),
+    };
+    Err(ast::NylispError::Raised(error))
+}
+
+// the pieces 🥱 templates are built from, called directly so shadowing 📜 or 🚃 can't break them
+fn quasi_list(args: Vec<ast::NylispExpression>) -> Result<ast::NylispExpression, ast::NylispError> {
+    Ok(ast::NylispExpression::List(args))
+}
+
+fn quasi_append(args: Vec<ast::NylispExpression>) -> Result<ast::NylispExpression, ast::NylispError> {
+    let mut items: Vec<ast::NylispExpression> = Vec::new();
+    for arg in args {
+        match arg.proper_list() {
+            Some(list) => items.extend(list),
+            None => return Err(ast::NylispError::Because(format!("{} requires a list to splice, got {}", tokenizer::tokenizer::UNQUOTE_SPLICING, arg))),
+        }
+    }
+    Ok(ast::NylispExpression::List(items))
+}
+
+fn quasi_dotted(args: Vec<ast::NylispExpression>) -> Result<ast::NylispExpression, ast::NylispError> {
+    let mut args = args.into_iter();
+    let (items, tail) = (args.next().unwrap(), args.next().unwrap());
+    let mut pair = tail;
+    for item in items.proper_list().unwrap_or_default().into_iter().rev() {
+        pair = ast::NylispExpression::Pair(Rc::new(item), Rc::new(pair));
+    }
+    Ok(pair)
+}
+
+fn quasi_quote(args: Vec<ast::NylispExpression>) -> Result<ast::NylispExpression, ast::NylispError> {
+    Ok(ast::NylispExpression::Quote(Rc::new(args.into_iter().next().unwrap())))
+}
+
+// 📇 constructor, gets the type name, the field names and then one value per field
+// the procedures 📇 defines always pass the right arguments, but quoted code can call these with anything
+fn record_new(args: Vec<ast::NylispExpression>) -> Result<ast::NylispExpression, ast::NylispError> {
+    let mut args = args.into_iter();
+    let name = match args.next() {
+        Some(ast::NylispExpression::Symbol(name)) => name,
+        other => return Err(ast::NylispError::of_kind("type", format!("📇 constructor requires a type name, got {:?}", other))),
+    };
+    let fields = match args.next().map(|f| f.proper_list()) {
+        Some(Some(fields)) => fields,
+        _ => return Err(ast::NylispError::of_kind("type", format!("📇 constructor for {} requires a list of fields", name))),
+    };
+    let fields = fields.into_iter().zip(args).map(|(field, value)| match field {
+        ast::NylispExpression::Symbol(field) => Ok((field, value)),
+        other => Err(ast::NylispError::of_kind("type", format!("📇 {} fields should be symbols, got {}", name, other))),
+    });
+    Ok(ast::NylispExpression::Record { name, fields: Rc::new(fields.collect::<Result<_, _>>()?) })
+}
+
+fn record_is(args: Vec<ast::NylispExpression>) -> Result<ast::NylispExpression, ast::NylispError> {
+    if args.len() != 2 {
+        return Err(ast::NylispError::of_kind("type", format!("📇 predicate requires a type name and a value, got {} arguments", args.len())));
+    }
+    let is = matches!((&args[0], &args[1]), (ast::NylispExpression::Symbol(name), ast::NylispExpression::Record { name: of, .. }) if name == of);
+    Ok(ast::NylispExpression::Boolean(is))
+}
+
+// fields of args[2] when it is a record of type args[0], the error names the procedure by its field args[1]
+fn record_fields<'a>(args: &'a [ast::NylispExpression], procedure: &str) -> Result<&'a [(ast::Symbol, ast::NylispExpression)], ast::NylispError> {
+    if args.len() < 3 {
+        return Err(ast::NylispError::of_kind("type", format!("📇 field accessor requires a type name, a field and a record, got {} arguments", args.len())));
+    }
+    match (&args[0], &args[2]) {
+        (ast::NylispExpression::Symbol(name), ast::NylispExpression::Record { name: of, fields }) if name == of => Ok(fields),
+        _ => Err(ast::NylispError::of_kind("type", format!("{}-{}{} requires a {}, got {}", args[0], procedure, args[1], args[0], args[2]))),
+    }
+}
+
+fn record_get(args: Vec<ast::NylispExpression>) -> Result<ast::NylispExpression, ast::NylispError> {
+    let fields = record_fields(&args, "")?;
+    Ok(fields.iter().find(|(field, _)| ast::NylispExpression::Symbol(*field) == args[1]).map(|(_, v)| v.clone()).unwrap_or(ast::NylispExpression::Nil))
+}
+
+// functional update, the record passed in is left alone
+fn record_with(args: Vec<ast::NylispExpression>) -> Result<ast::NylispExpression, ast::NylispError> {
+    if args.len() != 4 {
+        return Err(ast::NylispError::of_kind("type", format!("📇 with- requires a type name, a field, a record and a value, got {} arguments", args.len())));
+    }
+    let fields = record_fields(&args, "with-")?;
+    let fields = fields.iter().map(|(field, v)| {
+        if ast::NylispExpression::Symbol(*field) == args[1] { (*field, args[3].clone()) } else { (*field, v.clone()) }
+    }).collect();
+    Ok(ast::NylispExpression::Record { name: args[0].to_string().into(), fields: Rc::new(fields) })
+}
+
+// the cell a forced 🌊 gives, the tail is expected to be another stream
+fn stream_cell(args: Vec<ast::NylispExpression>) -> Result<ast::NylispExpression, ast::NylispError> {
+    match <[ast::NylispExpression; 2]>::try_from(args) {
+        Ok([head, tail]) => Ok(ast::NylispExpression::Pair(Rc::new(head), Rc::new(tail))),
+        Err(args) => Err(ast::NylispError::Because(format!("🌊 cells need an element and the rest of the stream, got {} values", args.len()))),
+    }
+}
+
+// whether value has the type a 📐 names, None when name isn't a type name but a predicate.
+// like 📇 accessors and 📥 options these are plain words, most of the fitting emoji are already
+// reader syntax (💬 strings, 🔤 chars, 🔑 keywords). the words and what they accept:
+//   any       every value
+//   number    numbers
+//   string    💬 strings
+//   boolean   👍 and 👎
+//   symbol    symbols
+//   keyword   🔑 keywords
+//   char      🔤 characters
+//   list      proper lists, whether written, built with 🍡 or a 💭 tail
+//   record    📇 records of any type
+//   function  anything that can be called: builtins, closures, keywords, continuations and coroutines
+fn named_type(name: &str, value: &ast::NylispExpression) -> Option<bool> {
+    Some(match name {
+        "any" => true,
+        "number" => matches!(value, ast::NylispExpression::Number(_)),
+        "string" => matches!(value, ast::NylispExpression::String(_)),
+        "boolean" => matches!(value, ast::NylispExpression::Boolean(_)),
+        "symbol" => matches!(value, ast::NylispExpression::Symbol(_)),
+        "keyword" => matches!(value, ast::NylispExpression::Keyword(_)),
+        "char" => matches!(value, ast::NylispExpression::Char(_)),
+        "list" => value.proper_list().is_some(),
+        "record" => matches!(value, ast::NylispExpression::Record { .. }),
+        "function" => matches!(value, ast::NylispExpression::Function(_)
+            | ast::NylispExpression::Closure { .. }
+            | ast::NylispExpression::Keyword(_)
+            | ast::NylispExpression::Continuation(_)
+            | ast::NylispExpression::DelimitedContinuation(_)
+            | ast::NylispExpression::Coroutine(_)),
+        _ => return None,
+    })
+}
+
+// like the 📇 helpers, only the code 📐 builds calls these with the right arguments
+fn contract_type(args: Vec<ast::NylispExpression>) -> Result<ast::NylispExpression, ast::NylispError> {
+    match args.as_slice() {
+        [name, value] => Ok(ast::NylispExpression::Boolean(named_type(&name.to_string(), value).unwrap_or(false))),
+        _ => Err(ast::NylispError::of_kind("type", format!("📐 type check requires a type name and a value, got {} arguments", args.len()))),
+    }
+}
+
+// function, "argument x" or "result", the contract, the value that broke it, and who is to blame
+fn contract_blame(args: Vec<ast::NylispExpression>) -> Result<ast::NylispExpression, ast::NylispError> {
+    match args.as_slice() {
+        [function, what, contract, value, blame] => Err(ast::NylispError::of_kind("contract", format!(
+            "{}: {} should be {}, got {} ({} is to blame)",
+            function, what, contract, value, blame,
+        ))),
+        _ => Err(ast::NylispError::of_kind("type", format!("📐 blame requires a function, a description, a contract, a value and a culprit, got {} arguments", args.len()))),
+    }
+}
+
+// what is left of a list after some of its items, as a list or a chain of pairs
+fn rest_of(items: &[ast::NylispExpression], tail: Option<ast::NylispExpression>) -> ast::NylispExpression {
+    let mut rest = tail.unwrap_or(ast::NylispExpression::List(vec![]));
+    for v in items.iter().rev() {
+        rest = ast::NylispExpression::Pair(Rc::new(v.clone()), Rc::new(rest));
+    }
+    match rest.proper_list() {
+        Some(items) => ast::NylispExpression::List(items),
+        None => rest,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::environment;
+    use super::*;
+
+    fn input_and_go(input: &str) -> Vec<Result<ast::NylispExpression, ast::NylispError>> {
+        let mut tokenizer_obj = tokenizer::tokenizer::Tokenizer::new(input.to_string());
+        let tokens = tokenizer_obj.tokenize();
+        let mut parser = parser::parser::Parser::new();
+        let (result, _) = parser.parse_program(tokens).unwrap();
+        let mut evaluator = Evaluator::new(vec![result]);
+        let result = evaluator.eval_programs(&environment::environment::builtin_env());
+
+        result
+    }
+
+    fn input_and_go_all(input: &str) -> Vec<Result<ast::NylispExpression, ast::NylispError>> {
+        let tokens = tokenizer::tokenizer::Tokenizer::new(input.to_string()).tokenize();
+        let programs = parser::parser::Parser::new().parse_programs(tokens).into_iter().map(|p| p.unwrap()).collect();
+        Evaluator::new(programs).eval_programs(&environment::environment::builtin_env())
+    }
+
+    // +
+    #[test]
+    fn eval_nylisp_test_plus() {
+        let input = "💖+ 1 2💔";
+        let expected: Result<ast::NylispExpression, ast::NylispError> = Ok(ast::NylispExpression::Number(3.0));
+        let got = input_and_go(input);
+        assert_eq!(got[0], expected);
+    }
+
+    // -
+    #[test]
+    fn eval_nylisp_test_minus() {
+        let input = "💖- 1 2💔";
+        let expected: Result<ast::NylispExpression, ast::NylispError> = Ok(ast::NylispExpression::Number(-1.0));
+        let got = input_and_go(input);
+        assert_eq!(got[0], expected);
+    }
+
+    // <>
+    #[test]
+    fn eval_nylisp_test_bigger_or_smaller() {
+        let input = "💖> 1 2 3 4💔";
+        let expected = ast::NylispExpression::Boolean(false);
+        let got = input_and_go(input);
+        assert_eq!(got[0], Ok(expected));
+
+        let input = "💖< 1 2 3 4💔";
+        let expected = ast::NylispExpression::Boolean(true);
+        let got = input_and_go(input);
+        assert_eq!(got[0], Ok(expected));
+    }
+
+    // car and quote
+    #[test]
+    fn eval_nylisp_test_car_quote() {
+        let input = "💖🚗😪💖👎 👍 👍💔💔";
         let expected = ast::NylispExpression::Boolean(false);
         let got = input_and_go(input);
         assert_eq!(got[0], Ok(expected));
@@ -328,22 +1725,22 @@ mod tests {
     #[test]
     fn eval_nylisp_insert_dat() {
         let input = "💖🌹 hoge 😪💖1 2 3💔💔";
-        let expected = ast::NylispExpression::List(vec![
-            ast::NylispExpression::Symbol("hoge".to_string()),
-            ast::NylispExpression::List(vec![
-                ast::NylispExpression::Number(1.0),
-                ast::NylispExpression::Number(2.0),
-                ast::NylispExpression::Number(3.0)])
-            , ast::NylispExpression::Boolean(true)]);
         let got = input_and_go(input);
-        assert_eq!(got[0], Ok(expected));
+        assert_eq!(got[0], Ok(ast::NylispExpression::Nil));
+
+        let got = input_and_go_all("💖🌹 hoge 😪💖1 2 3💔💔 hoge");
+        let expected = ast::NylispExpression::List(vec![
+            ast::NylispExpression::Number(1.0),
+            ast::NylispExpression::Number(2.0),
+            ast::NylispExpression::Number(3.0)]);
+        assert_eq!(got[1], Ok(expected));
     }
 
     // if statement
     #[test]
     fn eval_nylisp_if_statement() {
         let input = "💖🐶 💖🚗😪💖👎 👍 👍💔💔 😪ok 😪unexpected💔";
-        let expected = ast::NylispExpression::Symbol("unexpected".to_string());
+        let expected = ast::NylispExpression::Symbol("unexpected".into());
         let got = input_and_go(input);
         assert_eq!(got[0], Ok(expected));
     }
@@ -352,7 +1749,7 @@ mod tests {
     #[test]
     fn eval_nylisp_lambda() {
         let input = "💖💖🐷💖 x 💔 💖🚗 x💔💔😪💖ok no💔💔";
-        let expected = ast::NylispExpression::Symbol("ok".to_string());
+        let expected = ast::NylispExpression::Symbol("ok".into());
         let got = input_and_go(input);
         assert_eq!(got[0], Ok(expected));
     }
@@ -377,4 +1774,864 @@ mod tests {
             assert!(false);
         }
     }
-}
\ No newline at end of file
+
+    // cons onto a quoted list reads back as a plain list
+    #[test]
+    fn eval_nylisp_cons() {
+        let input = "💖🍡 1 😪💖2 3💔💔";
+        let expected = ast::NylispExpression::List(vec![
+            ast::NylispExpression::Number(1.0),
+            ast::NylispExpression::Number(2.0),
+            ast::NylispExpression::Number(3.0),
+        ]);
+        let got = input_and_go(input);
+        assert_eq!(got[0], Ok(expected));
+    }
+
+    // dotted pair and its cdr
+    #[test]
+    fn eval_nylisp_dotted_pair() {
+        let got = input_and_go("💖🍡 1 2💔");
+        assert_eq!(got[0].as_ref().unwrap().to_string(), "💖1 🔗 2 💔");
+
+        let got = input_and_go("💖💭 😪💖1 🔗 2💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(2.0)));
+    }
+
+    // 💭 of a list points into it instead of copying, and still compares and orders like a list
+    #[test]
+    fn eval_nylisp_cdr_shares_list() {
+        let got = input_and_go("💖💭 💖💭 😪💖1 2 3💔💔💔");
+        assert!(matches!(got[0], Ok(ast::NylispExpression::Tail(_))), "expected a shared tail, got {:?}", got[0]);
+        assert_eq!(got[0], Ok(ast::NylispExpression::List(vec![ast::NylispExpression::Number(3.0)])));
+
+        let got = input_and_go("💖🚗 💖💭 😪💖1 2 3💔💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(2.0)));
+        let got = input_and_go("💖🈳 💖💭 💖💭 💖💭 😪💖1 2 3💔💔💔💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Boolean(true)));
+
+        let got = input_and_go("💖💭 😪💖1 2💔💔");
+        assert!(got[0].as_ref().unwrap() < &ast::NylispExpression::List(vec![ast::NylispExpression::Number(3.0)]));
+    }
+
+    // list, append and reverse
+    #[test]
+    fn eval_nylisp_list_append_reverse() {
+        let input = "💖🔙 💖🚃 💖📜 1 2💔 😪💖3💔 💖🍡 4 😪💖💔💔💔💔";
+        let expected = ast::NylispExpression::List(vec![
+            ast::NylispExpression::Number(4.0),
+            ast::NylispExpression::Number(3.0),
+            ast::NylispExpression::Number(2.0),
+            ast::NylispExpression::Number(1.0),
+        ]);
+        let got = input_and_go(input);
+        assert_eq!(got[0], Ok(expected));
+    }
+
+    // length and null?
+    #[test]
+    fn eval_nylisp_length_null() {
+        let got = input_and_go("💖📏 💖🍡 1 😪💖2 3💔💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(3.0)));
+
+        let got = input_and_go("💖🈳 💖💭 😪💖1💔💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Boolean(true)));
+
+        let got = input_and_go("💖📏 😪💖1 🔗 2💔💔");
+        assert_eq!(got[0].as_ref().unwrap_err().to_string(), "expected a proper list, got a dotted pair");
+    }
+
+    // empty list, false and nil are all different things
+    #[test]
+    fn eval_nylisp_nil() {
+        let got = input_and_go("💖💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::List(vec![])));
+
+        let got = input_and_go("🫥");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Nil));
+
+        let got = input_and_go("💖= 🫥 👎💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Boolean(false)));
+    }
+
+    // only 👎 and 🫥 are falsy
+    #[test]
+    fn eval_nylisp_if_truthiness() {
+        let got = input_and_go("💖🐶 🫥 😪yes 😪no💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Symbol("no".into())));
+
+        let got = input_and_go("💖🐶 😪💖💔 😪yes 😪no💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Symbol("yes".into())));
+
+        let got = input_and_go("💖🐶 0 😪yes💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Symbol("yes".into())));
+
+        let got = input_and_go("💖🐶 👎 😪yes💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Nil));
+    }
+
+    // the branch not taken is never evaluated
+    #[test]
+    fn eval_nylisp_if_lazy() {
+        let got = input_and_go("💖🐶 👍 1 💖💩💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(1.0)));
+    }
+
+    // string->list splits into grapheme clusters and list->string joins them back
+    #[test]
+    fn eval_nylisp_string_list() {
+        let input = "💖🧶 😪a👍🏽1💔";
+        let expected = ast::NylispExpression::List(vec![
+            ast::NylispExpression::Char("a".to_string()),
+            ast::NylispExpression::Char("👍🏽".to_string()),
+            ast::NylispExpression::Char("1".to_string()),
+        ]);
+        let got = input_and_go(input);
+        assert_eq!(got[0], Ok(expected));
+
+        let got = input_and_go("💖🧵 💖🔙 💖🧶 😪ab💔💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::String("ba".to_string())));
+    }
+
+    // char comparison and classification
+    #[test]
+    fn eval_nylisp_char_predicates() {
+        let got = input_and_go("💖< 🔤a 🔤b 🔤c💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Boolean(true)));
+
+        let got = input_and_go("💖🎭 🔤👍🏽 🔤🍙💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Boolean(true)));
+
+        let got = input_and_go("💖🔢 🔤7💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Boolean(true)));
+
+        let got = input_and_go("💖🔠 🔤7💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Boolean(false)));
+    }
+
+    // codepoints round trip
+    #[test]
+    fn eval_nylisp_codepoints() {
+        let got = input_and_go("💖🧮 🔤👍🏽💔");
+        let expected = ast::NylispExpression::List(vec![
+            ast::NylispExpression::Number(0x1F44D as f64),
+            ast::NylispExpression::Number(0x1F3FD as f64),
+        ]);
+        assert_eq!(got[0], Ok(expected));
+
+        let got = input_and_go("💖🪄 128077 127997💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Char("👍🏽".to_string())));
+    }
+
+    // probing a name nobody used doesn't grow the symbol table
+    #[test]
+    fn eval_nylisp_lookup_does_not_intern() {
+        let env = environment::environment::builtin_env();
+        assert_eq!(ast::get("never-bound-probe", &env), None);
+        assert!(ast::Symbol::lookup("never-bound-probe").is_none());
+        assert!(ast::Symbol::lookup("🚗").is_some());
+    }
+
+    // keywords evaluate to themselves
+    #[test]
+    fn eval_nylisp_keyword() {
+        let got = input_and_go("🔑name");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Keyword("name".into())));
+
+        let got = input_and_go("💖= 🔑name 😪name💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Boolean(false)));
+    }
+
+    // keywords look themselves up in association lists
+    #[test]
+    fn eval_nylisp_keyword_lookup() {
+        let got = input_and_go("💖🔑age 😪💖💖🔑name 🔗 alice💔 💖🔑age 🔗 3💔💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(3.0)));
+
+        let got = input_and_go("💖🔑age 💖📜 💖🍡 🔑name 😪alice💔💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Nil));
+
+        let got = input_and_go("💖🔑age 😪💖💔 0💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(0.0)));
+
+        let got = input_and_go("💖🔑age 😪💖💖🔑name alice💔 💖🔑age 3💔💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(3.0)));
+
+        // an entry that is neither shape is reported instead of looking like a missing key
+        let got = input_and_go("💖🔑age 😪💖💖🔑age 3 4💔💔 0💔");
+        assert_eq!(got[0].as_ref().unwrap_err().to_string(), "🔑age association list entries should be 💖key value💔 or 💖key 🔗 value💔, got 💖🔑age 3 4 💔");
+    }
+
+    // let* sees earlier bindings
+    #[test]
+    fn eval_nylisp_sequential_let() {
+        let input = "💖🍢 💖💖x 2💔💖y 💖+ x 1💔💔💔 💖* x y💔💔";
+        let got = input_and_go(input);
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(6.0)));
+
+        let input = "💖🍙 💖💖x 2💔💖y 💖+ x 1💔💔💔 💖* x y💔💔";
+        let got = input_and_go(input);
+        assert_eq!(got[0].as_ref().unwrap_err().to_string(), "symbol x not found in environment");
+    }
+
+    // letrec allows mutually recursive local functions
+    #[test]
+    fn eval_nylisp_recursive_let() {
+        let input = "💖🍥 💖\
+            💖even 💖🐷 💖n💔 💖🐶 💖= n 0💔 👍 💖odd 💖- n 1💔💔💔💔💔\
+            💖odd 💖🐷 💖n💔 💖🐶 💖= n 0💔 👎 💖even 💖- n 1💔💔💔💔💔\
+        💔 💖even 7💔💔";
+        let got = input_and_go(input);
+        assert_eq!(got[0], Ok(ast::NylispExpression::Boolean(false)));
+    }
+
+    // named let loops by calling its own name
+    #[test]
+    fn eval_nylisp_named_let() {
+        let input = "💖🔁 fact 💖💖n 5💔💖acc 1💔💔 💖🐶 💖= n 0💔 acc 💖fact 💖- n 1💔 💖* acc n💔💔💔💔";
+        let got = input_and_go(input);
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(120.0)));
+    }
+
+    // the let forms share 🍙 diagnostics
+    #[test]
+    fn eval_nylisp_let_errors() {
+        let got = input_and_go("💖🍢 💖💖x💔💔 x💔");
+        assert_eq!(got[0].as_ref().unwrap_err().to_string(), "🍢 requires a list of 2 elements, got 1");
+
+        let got = input_and_go("💖🍥 x x💔");
+        assert_eq!(got[0].as_ref().unwrap_err().to_string(), "🍥 first element should be a list, but got Symbol(\"x\")");
+
+        let got = input_and_go("💖🍙 💖💖x 1 2💔💔 x💔");
+        assert_eq!(got[0].as_ref().unwrap_err().to_string(), "🍙 requires a list of 2 elements, got 3");
+    }
+
+    // 👣 returns its last value, 🫥 when empty
+    #[test]
+    fn eval_nylisp_begin() {
+        let got = input_and_go("💖👣 1 2 3💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(3.0)));
+
+        let got = input_and_go("💖👣💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Nil));
+    }
+
+    // closure bodies can define locals before using them
+    #[test]
+    fn eval_nylisp_closure_body_sequence() {
+        let input = "💖💖🐷 💖x💔 💖🌹 y 💖* x 2💔💔 💖+ x y💔💔 3💔";
+        let got = input_and_go(input);
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(9.0)));
+    }
+
+    // let bodies are sequenced too
+    #[test]
+    fn eval_nylisp_let_body_sequence() {
+        let input = "💖🍙 💖💖x 1💔💔 💖🌹 x 5💔 💖+ x 1💔💔";
+        let got = input_and_go(input);
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(6.0)));
+    }
+
+    // 📝 updates the outer binding instead of shadowing it
+    #[test]
+    fn eval_nylisp_set() {
+        let input = "💖🌹 count 0💔 💖🌹 bump 💖🐷 💖💔 💖📝 count 💖+ count 1💔💔💔💔 💖bump💔 💖bump💔 count";
+        let got = input_and_go_all(input);
+        assert_eq!(got[4], Ok(ast::NylispExpression::Number(2.0)));
+
+        let got = input_and_go("💖📝 nowhere 1💔");
+        assert_eq!(got[0].as_ref().unwrap_err().to_string(), "📝 can't set nowhere, it is not bound");
+    }
+
+    // closures keep the frame they were made in, so counters work
+    #[test]
+    fn eval_nylisp_closure_captures_frame() {
+        let input = "💖🌹 make-counter 💖🐷 💖💔 💖🍙 💖💖n 0💔💔 💖🐷 💖💔 💖📝 n 💖+ n 1💔💔 n💔💔💔💔 \
+            💖🌹 c 💖make-counter💔💔 💖c💔 💖c💔 💖c💔";
+        let got = input_and_go_all(input);
+        assert_eq!(got[4], Ok(ast::NylispExpression::Number(3.0)));
+    }
+
+    // functions defined in a frame don't keep that frame alive once nothing else uses it
+    #[test]
+    fn eval_nylisp_frames_are_freed() {
+        let input = "💖🌹 count 💖🐷 💖n💔 💖🐶 💖= n 0💔 0 💖count 💖- n 1💔💔💔💔💔 💖count 3💔 \
+            💖🌹 outer 💖🐷 💖💔 💖🌹 inner 💖🐷 💖💔 1💔💔 inner💔💔 💖💖outer💔💔";
+        let tokens = tokenizer::tokenizer::Tokenizer::new(input.to_string()).tokenize();
+        let programs = parser::parser::Parser::new().parse_programs(tokens).into_iter().map(|p| p.unwrap()).collect();
+        let env = environment::environment::builtin_env();
+        let frame = Rc::downgrade(&env.data);
+        let got = Evaluator::new(programs).eval_programs(&env);
+        assert_eq!(got[3], Ok(ast::NylispExpression::Number(1.0)));
+        drop(got);
+        drop(env);
+        assert!(frame.upgrade().is_none());
+    }
+
+    // 🚦 picks the first truthy clause and never evaluates the rest
+    #[test]
+    fn eval_nylisp_cond() {
+        let input = "💖🚦 💖💖> 1 2💔 😪bigger💔 💖💖< 1 2💔 😪smaller💔 💖🤷 💖💩💔💔💔";
+        let got = input_and_go(input);
+        assert_eq!(got[0], Ok(ast::NylispExpression::Symbol("smaller".into())));
+
+        let got = input_and_go("💖🚦 💖👎 1💔 💖🤷 1 2💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(2.0)));
+
+        let got = input_and_go("💖🚦 💖👎 1💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Nil));
+    }
+
+    // 🔀 matches literal values
+    #[test]
+    fn eval_nylisp_case() {
+        let input = "💖🔀 💖+ 1 2💔 💖💖1 2💔 😪low💔 💖💖3 4💔 😪high💔 💖🤷 😪other💔💔";
+        let got = input_and_go(input);
+        assert_eq!(got[0], Ok(ast::NylispExpression::Symbol("high".into())));
+
+        let got = input_and_go("💖🔀 🔑b 💖💖🔑a💔 1💔 💖🤷 2💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(2.0)));
+
+        let got = input_and_go("💖🔀 5 💖💖1💔 1💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Nil));
+    }
+
+    // ✅ and ⛔
+    #[test]
+    fn eval_nylisp_when_unless() {
+        let got = input_and_go("💖✅ 👍 1 2💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(2.0)));
+
+        let got = input_and_go("💖✅ 👎 💖💩💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Nil));
+
+        let got = input_and_go("💖⛔ 👎 1💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(1.0)));
+    }
+
+    // 😎 and 😕 short-circuit and return the deciding value
+    #[test]
+    fn eval_nylisp_and_or() {
+        let got = input_and_go("💖😎 1 🫥 💖💩💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Nil));
+
+        let got = input_and_go("💖😎 1 🔑a 3💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(3.0)));
+
+        let got = input_and_go("💖😕 👎 🫥 🔑found 💖💩💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Keyword("found".into())));
+
+        let got = input_and_go("💖😕 👎 🫥💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Nil));
+
+        let got = input_and_go("💖😎💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Boolean(true)));
+    }
+
+    // the usual guard idiom, 🚗 is only reached for non-empty lists
+    #[test]
+    fn eval_nylisp_or_guard() {
+        let got = input_and_go("💖😕 💖🈳 😪💖💔💔 💖🚗 😪💖💔💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Boolean(true)));
+    }
+
+    // rest parameters collect the extra arguments
+    #[test]
+    fn eval_nylisp_rest_params() {
+        let got = input_and_go("💖💖🐷 💖a 🔗 more💔 💖🍡 a more💔💔 1 2 3💔");
+        assert_eq!(got[0].as_ref().unwrap().to_string(), "💖1 2 3 💔");
+
+        let got = input_and_go("💖💖🐷 all all💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::List(vec![])));
+    }
+
+    // optional parameters fall back to their defaults, which can see earlier parameters
+    #[test]
+    fn eval_nylisp_optional_params() {
+        let got = input_and_go("💖💖🐷 💖a ❓ 💖b 💖* a 2💔💔 c💔 💖📜 a b c💔💔 5💔");
+        assert_eq!(got[0].as_ref().unwrap().to_string(), "💖5 10 🫥 💔");
+
+        let got = input_and_go("💖💖🐷 💖a ❓ 💖b 💖* a 2💔💔💔 💖+ a b💔💔 5 1💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(6.0)));
+    }
+
+    // keyword arguments can come in any order
+    #[test]
+    fn eval_nylisp_keyword_params() {
+        let got = input_and_go("💖💖🐷 💖a 🔐 💖x 0💔 💖y 0💔💔 💖📜 a x y💔💔 1 🔑y 3💔");
+        assert_eq!(got[0].as_ref().unwrap().to_string(), "💖1 0 3 💔");
+
+        let got = input_and_go("💖💖🐷 💖🔐 x💔 x💔 🔑z 1💔");
+        assert_eq!(got[0].as_ref().unwrap_err().to_string(), "🐷 💖🔐 x 💔 takes exactly 0 arguments plus keywords 🔑x, got an unexpected 🔑z");
+    }
+
+    // arity errors describe what the closure accepts
+    #[test]
+    fn eval_nylisp_arity_errors() {
+        let got = input_and_go("💖💖🐷 💖a b💔 a💔 1💔");
+        assert_eq!(got[0].as_ref().unwrap_err().to_string(), "🐷 💖a b 💔 takes exactly 2 arguments, got 1");
+
+        let got = input_and_go("💖💖🐷 💖a ❓ b💔 a💔 1 2 3💔");
+        assert_eq!(got[0].as_ref().unwrap_err().to_string(), "🐷 💖a ❓ b 💔 takes 1 to 2 arguments, got 3");
+
+        let got = input_and_go("💖💖🐷 💖a b 🔗 c💔 a💔💔");
+        assert_eq!(got[0].as_ref().unwrap_err().to_string(), "🐷 💖a b 🔗 c 💔 takes at least 2 arguments, got 0");
+    }
+
+    // 💬 string literals evaluate to themselves
+    #[test]
+    fn eval_nylisp_string_literal() {
+        let got = input_and_go("💬hi 💖there💔💬");
+        assert_eq!(got[0], Ok(ast::NylispExpression::String("hi 💖there💔".to_string())));
+
+        let got = input_and_go("💖= 💬a💬 💬a💬💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Boolean(true)));
+    }
+
+    // 🍳 defines a named closure that can recurse
+    #[test]
+    fn eval_nylisp_defun() {
+        let input = "💖🍳 fact 💖n💔 💬factorial of n💬 💖🐶 💖= n 0💔 1 💖* n 💖fact 💖- n 1💔💔💔💔💔 💖fact 5💔 fact 💖📖 fact💔";
+        let got = input_and_go_all(input);
+        assert_eq!(got[0], Ok(ast::NylispExpression::Nil));
+        assert_eq!(got[1], Ok(ast::NylispExpression::Number(120.0)));
+        assert_eq!(got[2].as_ref().unwrap().to_string(), "<closure fact>");
+        assert_eq!(got[3], Ok(ast::NylispExpression::String("factorial of n".to_string())));
+    }
+
+    // several body expressions, and a lone string is the body rather than a docstring
+    #[test]
+    fn eval_nylisp_defun_body() {
+        let input = "💖🍳 f 💖x💔 💖🌹 y 1💔 💖+ x y💔💔 💖f 1💔 💖🍳 g 💖💔 💬hi💬💔 💖g💔 💖📖 g💔";
+        let got = input_and_go_all(input);
+        assert_eq!(got[1], Ok(ast::NylispExpression::Number(2.0)));
+        assert_eq!(got[3], Ok(ast::NylispExpression::String("hi".to_string())));
+        assert_eq!(got[4], Ok(ast::NylispExpression::Nil));
+    }
+
+    // errors raised inside named closures list the functions they passed through
+    #[test]
+    fn eval_nylisp_defun_trace() {
+        let input = "💖🍳 inner 💖💔 missing💔 💖🍳 outer 💖💔 💖inner💔💔 💖outer💔";
+        let got = input_and_go_all(input);
+        assert_eq!(got[2].as_ref().unwrap_err().to_string(), "symbol missing not found in environment\n    in inner\n    in outer");
+    }
+
+    // 🥅 catches raised values and builtin errors alike
+    #[test]
+    fn eval_nylisp_try_catch() {
+        let got = input_and_go("💖🥅 💖+ 1 💖🧨 🔑oops💔💔 💖🎣 e 💖🎁 e💔💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Keyword("oops".into())));
+
+        let got = input_and_go("💖🥅 💖+ 1 👍💔 💖🎣 e 💖🆔 e💔💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Keyword("type".into())));
+
+        let got = input_and_go("💖🥅 missing 💖🎣 e 💖📜 💖🆔 e💔 💖📨 e💔💔💔💔");
+        assert_eq!(got[0].as_ref().unwrap().to_string(), "💖🔑unbound symbol missing not found in environment 💔");
+
+        let got = input_and_go("💖🥅 1 2 💖🎣 e 3💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(2.0)));
+    }
+
+    // errors raised with a kind and message keep them
+    #[test]
+    fn eval_nylisp_raise_kind() {
+        let got = input_and_go("💖🥅 💖🧨 🔑bad-input 💬no good💬 42💔 💖🎣 e 💖📜 💖🆔 e💔 💖🎁 e💔💔💔💔");
+        assert_eq!(got[0].as_ref().unwrap().to_string(), "💖🔑bad-input 42 💔");
+
+        let got = input_and_go("💖🧨 🔑bad-input 💬no good💬💔");
+        assert_eq!(got[0].as_ref().unwrap_err().to_string(), "no good");
+    }
+
+    // 🧹 runs whether or not the body failed
+    #[test]
+    fn eval_nylisp_try_finally() {
+        let input = "💖🌹 cleaned 0💔 \
+            💖🥅 💖🥅 💖🧨 1💔 💖🧹 💖📝 cleaned 1💔💔💔 💖🎣 e 💖🎁 e💔💔💔 \
+            cleaned";
+        let got = input_and_go_all(input);
+        assert_eq!(got[1], Ok(ast::NylispExpression::Number(1.0)));
+        assert_eq!(got[2], Ok(ast::NylispExpression::Number(1.0)));
+
+        let got = input_and_go("💖🥅 5 💖🎣 e 0💔 💖🧹 6💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(5.0)));
+    }
+
+    // errors are caught even from inside named closures
+    #[test]
+    fn eval_nylisp_catch_through_defun() {
+        let input = "💖🍳 f 💖💔 💖🧨 🔑inner💔💔 💖🥅 💖f💔 💖🎣 e 💖🎁 e💔💔💔";
+        let got = input_and_go_all(input);
+        assert_eq!(got[1], Ok(ast::NylispExpression::Keyword("inner".into())));
+    }
+
+    // calling the continuation escapes from the middle of a computation
+    #[test]
+    fn eval_nylisp_callcc_escape() {
+        let got = input_and_go("💖+ 1 💖🔖 💖🐷 💖k💔 💖* 10 💖k 2💔💔💔💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(3.0)));
+
+        let input = "💖🍳 first-negative 💖xs💔 💖🔖 💖🐷 💖return💔 \
+            💖🔁 walk 💖💖l xs💔💔 💖🐶 💖🈳 l💔 🫥 💖👣 💖✅ 💖< 💖🚗 l💔 0💔 💖return 💖🚗 l💔💔💔 💖walk 💖💭 l💔💔💔💔💔💔💔💔 \
+            💖first-negative 😪💖3 -2 5 -7💔💔 💖first-negative 😪💖1 2💔💔";
+        let got = input_and_go_all(input);
+        assert_eq!(got[1], Ok(ast::NylispExpression::Number(-2.0)));
+        assert_eq!(got[2], Ok(ast::NylispExpression::Nil));
+    }
+
+    // a saved continuation can be resumed more than once
+    #[test]
+    fn eval_nylisp_callcc_reentry() {
+        let input = "💖🍙 💖💖n 0💔 💖again 🫥💔💔 \
+            💖🌹 x 💖🔖 💖🐷 💖k💔 💖📝 again k💔 0💔💔💔 \
+            💖📝 n 💖+ n 1💔💔 \
+            💖🐶 💖< n 3💔 💖again n💔 💖📜 x n💔💔💔";
+        let got = input_and_go(input);
+        assert_eq!(got[0].as_ref().unwrap().to_string(), "💖2 3 💔");
+
+        let got = input_and_go("💖🔖 💖🐷 💖k💔 k💔💔");
+        assert_eq!(got[0].as_ref().unwrap().to_string(), "<continuation>");
+    }
+
+    // frames are on the heap, so deep non-tail recursion doesn't overflow the stack
+    #[test]
+    fn eval_nylisp_deep_recursion() {
+        let input = "💖🍳 sum 💖n💔 💖🐶 💖= n 0💔 0 💖+ n 💖sum 💖- n 1💔💔💔💔💔 💖sum 10000💔";
+        let got = input_and_go_all(input);
+        assert_eq!(got[1], Ok(ast::NylispExpression::Number(50005000.0)));
+    }
+
+    // 🪝 hands over the rest of the 🚧 body, which can be called any number of times
+    #[test]
+    fn eval_nylisp_shift_reset() {
+        let got = input_and_go("💖🚧 💖* 2 💖🪝 k 5💔💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(5.0)));
+
+        let got = input_and_go("💖+ 1 💖🚧 💖+ 1 💖🪝 k 💖k 💖k 10💔💔💔💔💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(13.0)));
+
+        let got = input_and_go("💖🪝 k 1💔");
+        assert_eq!(got[0].as_ref().unwrap_err().to_string(), "🪝 can only be used inside 🚧");
+    }
+
+    // generators written in nylisp itself, every 🪝 yields one element
+    #[test]
+    fn eval_nylisp_shift_reset_generator() {
+        let input = "💖🍳 yield 💖x💔 💖🪝 k 💖🍡 x 💖k 🫥💔💔💔💔 \
+            💖🚧 💖yield 1💔 💖yield 2💔 💖yield 3💔 😪💖💔💔 \
+            💖🚧 💖🔁 loop 💖💖i 0💔💔 💖✅ 💖< i 3💔 💖yield 💖* i i💔💔 💖loop 💖+ i 1💔💔💔💔 😪💖💔💔";
+        let got = input_and_go_all(input);
+        assert_eq!(got[1].as_ref().unwrap().to_string(), "💖1 2 3 💔");
+        assert_eq!(got[2].as_ref().unwrap().to_string(), "💖0 1 4 💔");
+    }
+
+    // 🥱 templates fill in 😳 values and splice 🤯 lists
+    #[test]
+    fn eval_nylisp_quasiquote() {
+        let got = input_and_go("💖🍙 💖💖x 1💔 💖ys 😪💖2 3💔💔💔 🥱💖a 😳x 🤯ys 😳💖+ x 1💔 😪😳x💔💔");
+        assert_eq!(got[0].as_ref().unwrap().to_string(), "💖a 1 2 3 2 '1 💔");
+
+        let got = input_and_go("💖🍙 💖💖x 1💔💔 🥱💖a 🔗 😳x💔💔");
+        assert_eq!(got[0].as_ref().unwrap().to_string(), "💖a 🔗 1 💔");
+
+        let got = input_and_go("🥱💖a 🥱💖b 😳😳💖+ 1 2💔💔💔");
+        assert_eq!(got[0].as_ref().unwrap().to_string(), "💖a 💖🥱 💖b 💖😳 3 💔 💔 💔 💔");
+
+        let got = input_and_go("😳x");
+        assert_eq!(got[0].as_ref().unwrap_err().to_string(), "😳 can only be used inside 🥱");
+    }
+
+    // 🧙 transformers get their arguments unevaluated, 🔍 shows what they expand to
+    #[test]
+    fn eval_nylisp_defmacro() {
+        let input = "💖🧙 my-unless 💖test 🔗 body💔 🥱💖🐶 😳test 🫥 💖👣 🤯body💔💔💔 \
+            💖my-unless 👎 1 2💔 💖my-unless 👍 💖💩💔💔 \
+            💖🔍 😪💖my-unless 👎 1 2💔💔 my-unless";
+        let got = input_and_go_all(input);
+        assert_eq!(got[1], Ok(ast::NylispExpression::Number(2.0)));
+        assert_eq!(got[2], Ok(ast::NylispExpression::Nil));
+        assert_eq!(got[3].as_ref().unwrap().to_string(), "💖🐶 false 🫥 💖👣 1 2 💔 💔");
+        assert_eq!(got[4].as_ref().unwrap().to_string(), "<macro my-unless>");
+    }
+
+    // 🆕 names can't capture the caller's variables
+    #[test]
+    fn eval_nylisp_gensym() {
+        let input = "💖🧙 swap 💖a b💔 💖🍙 💖💖tmp 💖🆕💔💔💔 \
+                🥱💖🍙 💖💖😳tmp 😳a💔💔 💖📝 😳a 😳b💔 💖📝 😳b 😳tmp💔💔💔💔 \
+            💖🌹 tmp 1💔 💖🌹 other 2💔 💖swap tmp other💔 💖📜 tmp other💔";
+        let got = input_and_go_all(input);
+        assert_eq!(got[4].as_ref().unwrap().to_string(), "💖2 1 💔");
+
+        let got = input_and_go("💖= 💖🆕💔 💖🆕💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Boolean(false)));
+    }
+
+    // 🧬 rules with 🔂 repetition, expanded before the programs run
+    #[test]
+    fn eval_nylisp_syntax_rules() {
+        let input = "💖🧬 my-let 💖💔 💖💖my-let 💖💖name val💔 🔂💔 body 🔂💔 💖💖🐷 💖name 🔂💔 body 🔂💔 val 🔂💔💔💔 \
+            💖my-let 💖💖a 1💔 💖b 2💔💔 💖+ a b💔💔 \
+            💖🔍 😪💖my-let 💖💖a 1💔💔 a💔💔 \
+            💖my-let 1 2💔";
+        let got = input_and_go_all(input);
+        assert_eq!(got[0], Ok(ast::NylispExpression::Nil));
+        assert_eq!(got[1], Ok(ast::NylispExpression::Number(3.0)));
+        assert_eq!(got[2].as_ref().unwrap().to_string(), "💖💖🐷 💖a 💔 a 💔 1 💔");
+        assert_eq!(got[3].as_ref().unwrap_err().to_string(), "my-let has no rule matching 💖my-let 1 2 💔");
+    }
+
+    // names the template binds can't capture the user's, literals have to match exactly
+    #[test]
+    fn eval_nylisp_syntax_rules_hygiene() {
+        let input = "💖🧬 my-or 💖💔 💖💖my-or💔 👎💔 💖💖my-or e💔 e💔 \
+                💖💖my-or e r 🔂💔 💖🍙 💖💖t e💔💔 💖🐶 t t 💖my-or r 🔂💔💔💔💔💔 \
+            💖🌹 t 5💔 💖my-or 👎 t💔 \
+            💖🧬 for 💖in💔 💖💖for x in lst body 🔂💔 \
+                💖🔁 loop 💖💖l lst💔💔 💖🐶 💖🈳 l💔 🫥 💖👣 💖🍙 💖💖x 💖🚗 l💔💔💔 body 🔂💔 💖loop 💖💭 l💔💔💔💔💔💔💔 \
+            💖🌹 total 0💔 \
+            💖🍙 💖💖l 10💔💔 💖for n in 😪💖1 2💔 💖📝 total 💖+ total n l💔💔💔💔 total \
+            💖for n of 😪💖1💔 n💔";
+        let got = input_and_go_all(input);
+        assert_eq!(got[2], Ok(ast::NylispExpression::Number(5.0)));
+        assert_eq!(got[6], Ok(ast::NylispExpression::Number(23.0)));
+        assert!(got[7].is_err());
+    }
+
+    // names bound around a use hide the macro, and a 🧬 inside other code only binds when and where it runs
+    #[test]
+    fn eval_nylisp_syntax_rules_scope() {
+        let input = "💖🧬 my-or 💖💔 💖💖my-or💔 👎💔 💖💖my-or e💔 e💔 💖💖my-or e r 🔂💔 💖🍙 💖💖t e💔💔 💖🐶 t t 💖my-or r 🔂💔💔💔💔💔 \
+            💖🍙 💖💖my-or 5💔💔 my-or💔 \
+            💖💖🐷 💖my-or💔 my-or💔 3💔 \
+            💖🍙 💖💖my-or 💖🐷 💖x💔 💖* 2 x💔💔💔💔 💖my-or 4💔💔 \
+            💖🍢 💖💖a 1💔 💖b 💖my-or 👎 a💔💔💔 b💔 \
+            💖🧩 😪💖1 2💔 💖💖my-or x💔 x💔💔";
+        let got = input_and_go_all(input);
+        let got: Vec<String> = got[1..].iter().map(|r| r.as_ref().unwrap().to_string()).collect();
+        assert_eq!(got, vec!["5", "3", "8", "1", "2"]);
+
+        let input = "💖🐶 👎 💖🧬 m 💖💔 💖💖m💔 1💔💔 0💔 💖m💔 \
+            💖🍳 f 💖💔 💖🧬 twice 💖💔 💖💖twice e💔 💖+ e e💔💔💔 💖twice 3💔💔 💖f💔 💖twice 1💔 \
+            💖🐶 👍 💖🧬 m 💖💔 💖💖m💔 2💔💔 0💔 💖m💔";
+        let got = input_and_go_all(input);
+        assert_eq!(got[0], Ok(ast::NylispExpression::Number(0.0)));
+        assert!(got[1].is_err());
+        assert_eq!(got[3], Ok(ast::NylispExpression::Number(6.0)));
+        assert!(got[4].is_err());
+        assert_eq!(got[6], Ok(ast::NylispExpression::Number(2.0)));
+    }
+
+    // 🧩 tries literal, binding, wildcard, quoted and list patterns in order
+    #[test]
+    fn eval_nylisp_match() {
+        let input = "💖🍳 describe 💖x💔 💖🧩 x \
+                💖0 😪zero💔 \
+                💖💬hi💬 😪greeting💔 \
+                💖😪a 😪the-symbol-a💔 \
+                💖💖💔 😪empty💔 \
+                💖💖n💔 💖📜 😪one n💔💔 \
+                💖💖🤷 🤷 🔗 rest💔 💖📜 😪many rest💔💔 \
+                💖🔑k 😪keyword💔 \
+                💖🤷 😪other💔💔💔 \
+            💖describe 0💔 💖describe 💬hi💬💔 💖describe 😪a💔 💖describe 😪💖💔💔 💖describe 😪💖7💔💔 \
+            💖describe 😪💖1 2 3 4💔💔 💖describe 🔑k💔 💖describe 😪b💔";
+        let got = input_and_go_all(input);
+        let got: Vec<String> = got[1..].iter().map(|r| r.as_ref().unwrap().to_string()).collect();
+        assert_eq!(got, vec!["zero", "greeting", "the-symbol-a", "empty", "💖one 7 💔", "💖many 💖3 4 💔 💔", "keyword", "other"]);
+    }
+
+    // nested lists destructure in one go, ✅ guards can reject a match
+    #[test]
+    fn eval_nylisp_match_nested_and_guard() {
+        let input = "💖🧩 😪💖💖1 2💔 💖3 🔗 4💔💔 💖💖💖a b💔 💖c 🔗 d💔💔 💖📜 a b c d💔💔💔";
+        let got = input_and_go(input);
+        assert_eq!(got[0].as_ref().unwrap().to_string(), "💖1 2 3 4 💔");
+
+        let input = "💖🍳 sign 💖n💔 💖🧩 n 💖x ✅ 💖< x 0💔 😪negative💔 💖0 😪zero💔 💖x ✅ 💖> x 0💔 😪positive💔💔💔 \
+            💖sign -3💔 💖sign 0💔 💖sign 8💔";
+        let got = input_and_go_all(input);
+        assert_eq!(got[1], Ok(ast::NylispExpression::Symbol("negative".into())));
+        assert_eq!(got[2], Ok(ast::NylispExpression::Symbol("zero".into())));
+        assert_eq!(got[3], Ok(ast::NylispExpression::Symbol("positive".into())));
+
+        let got = input_and_go("💖🧩 😪💖1 1💔 💖💖x x💔 😪same💔 💖🤷 😪different💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Symbol("same".into())));
+    }
+
+    // nothing matching is an error scripts can catch
+    #[test]
+    fn eval_nylisp_match_non_exhaustive() {
+        let got = input_and_go("💖🧩 😪💖1 2💔 💖💖x💔 x💔💔");
+        assert_eq!(got[0].as_ref().unwrap_err().to_string(), "🧩 no pattern matches 💖1 2 💔");
+
+        let got = input_and_go("💖🥅 💖🧩 5 💖0 0💔💔 💖🎣 e 💖🆔 e💔💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Keyword("match".into())));
+    }
+
+    // 🐷 parameters and 🍙 bindings can be nested list patterns
+    #[test]
+    fn eval_nylisp_destructuring() {
+        let input = "💖🍳 swap 💖💖a b💔 c💔 💖📜 b a c💔💔 💖swap 😪💖1 2💔 3💔 \
+            💖🍙 💖💖💖x 🔗 xs💔 😪💖1 2 3💔💔 💖💖🤷 y💔 😪💖4 5💔💔💔 💖📜 x xs y💔💔 \
+            💖🍢 💖💖💖p q💔 😪💖6 7💔💔 💖r 💖+ p q💔💔💔 r💔";
+        let got = input_and_go_all(input);
+        assert_eq!(got[1].as_ref().unwrap().to_string(), "💖2 1 3 💔");
+        assert_eq!(got[2].as_ref().unwrap().to_string(), "💖1 💖2 3 💔 5 💔");
+        assert_eq!(got[3], Ok(ast::NylispExpression::Number(13.0)));
+
+        let got = input_and_go("💖💖🐷 💖💖a b💔💔 a💔 😪💖1 2 3💔💔");
+        assert_eq!(got[0].as_ref().unwrap_err().to_string(), "expected a list of 2 elements for 💖a b 💔, got 💖1 2 3 💔");
+        let got = input_and_go("💖🍙 💖💖💖a 🔗 b💔 5💔💔 a💔");
+        assert_eq!(got[0].as_ref().unwrap_err().to_string(), "expected a list for 💖a 🔗 b 💔, got 5");
+        let got = input_and_go("💖🥅 💖🍙 💖💖💖a b💔 😪💖1💔💔💔 a💔 💖🎣 e 💖🆔 e💔💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Keyword("shape".into())));
+        assert!(input_and_go("💖🍙 💖💖💖a 1💔 😪💖1 1💔💔💔 a💔")[0].is_err());
+    }
+
+    // 📇 defines a constructor, a predicate, accessors and functional updaters
+    #[test]
+    fn eval_nylisp_record() {
+        let input = "💖📇 point 💖x y💔💔 💖🌹 p 💖point 1 2💔💔 p \
+            💖point? p💔 💖point? 😪💖1 2💔💔 💖point-x p💔 💖point-with-y p 5💔 p \
+            💖= p 💖point 1 2💔💔 💖= p 💖point 2 1💔💔 💖🔑y p💔 💖point-x 3💔";
+        let got = input_and_go_all(input);
+        let shown: Vec<String> = got[2..11].iter().map(|r| r.as_ref().unwrap().to_string()).collect();
+        assert_eq!(shown, vec!["#<point x=1 y=2>", "true", "false", "1", "#<point x=1 y=5>", "#<point x=1 y=2>", "true", "false", "2"]);
+        assert_eq!(got[11].as_ref().unwrap_err().to_string(), "point-x requires a point, got 3\n    in point-x");
+
+        assert!(input_and_go("💖📇 pair 💖a a💔💔")[0].is_err());
+        assert!(input_and_go_all("💖📇 pair 💖a b💔💔 💖pair 1💔")[1].is_err());
+
+        // the helpers behind the procedures can be reached from built code, bad arguments are errors not panics
+        let number = || ast::NylispExpression::Number(1.0);
+        assert!(record_new(vec![]).is_err());
+        assert!(record_new(vec![number(), number()]).is_err());
+        assert!(record_new(vec![ast::NylispExpression::Symbol("point".into()), number()]).is_err());
+        assert!(record_new(vec![ast::NylispExpression::Symbol("point".into()), ast::NylispExpression::List(vec![number()]), number()]).is_err());
+        assert!(record_is(vec![number()]).is_err());
+        assert!(record_get(vec![number(), number()]).is_err());
+        assert!(record_with(vec![number(), number(), number()]).is_err());
+    }
+
+    // 🧩 takes records apart by type name and field position
+    #[test]
+    fn eval_nylisp_match_record() {
+        let input = "💖📇 point 💖x y💔💔 💖📇 size 💖x y💔💔 \
+            💖🍳 where 💖p💔 💖🧩 p 💖💖point 0 y💔 💖📜 😪on-y y💔💔 💖💖point x y💔 💖+ x y💔💔 💖🤷 😪not-a-point💔💔💔 \
+            💖where 💖point 0 4💔💔 💖where 💖point 1 2💔💔 💖where 💖size 1 2💔💔";
+        let got = input_and_go_all(input);
+        let got: Vec<String> = got[3..].iter().map(|r| r.as_ref().unwrap().to_string()).collect();
+        assert_eq!(got, vec!["💖on-y 4 💔", "3", "not-a-point"]);
+    }
+
+    // 💤 runs its expression once, on the first ⏰
+    #[test]
+    fn eval_nylisp_delay_force() {
+        let input = "💖🌹 runs 0💔 💖🌹 p 💖💤 💖👣 💖📝 runs 💖+ runs 1💔💔 42💔💔💔 runs \
+            💖⏰ p💔 💖⏰ p💔 runs 💖⏰ 7💔";
+        let got = input_and_go_all(input);
+        let got: Vec<String> = got[2..].iter().map(|r| r.as_ref().unwrap().to_string()).collect();
+        assert_eq!(got, vec!["0", "42", "42", "1", "7"]);
+    }
+
+    // endless streams only compute what is taken
+    #[test]
+    fn eval_nylisp_streams() {
+        let input = "💖🤏 5 💖🏭 💖🐷 💖x💔 💖* x x💔💔 💖🧺 💖🐷 💖x💔 💖= 0 💖% x 2💔💔💔 💖🪜 1💔💔💔💔 \
+            💖🤏 10 💖🪜 0 10 3💔💔 \
+            💖🤏 3 💖🦘 2 💖🌀 💖🐷 💖x💔 💖* 2 x💔💔 1💔💔💔 \
+            💖🍳 ones 💖💔 💖🌊 1 💖ones💔💔💔 💖🤏 2 💖ones💔💔 \
+            💖🤏 3 💖🪜 5 0 -2💔💔";
+        let got = input_and_go_all(input);
+        let got: Vec<String> = got.iter().map(|r| r.as_ref().unwrap().to_string()).collect();
+        assert_eq!(got, vec!["💖4 16 36 64 100 💔", "💖0 3 6 9 💔", "💖4 8 16 💔", "🫥", "💖1 1 💔", "💖5 3 1 💔"]);
+
+        assert!(stream_cell(vec![ast::NylispExpression::Number(1.0)]).is_err());
+
+        // the prelude is evaluated once, every builtin environment gets the same closures
+        let (a, b) = (environment::environment::builtin_env(), environment::environment::builtin_env());
+        assert_eq!(ast::get("🤏", &a), ast::get("🤏", &b));
+    }
+
+    // 🫳 hands values out one at a time, 🎬 can send values back in
+    #[test]
+    fn eval_nylisp_coroutine() {
+        let input = "💖🌹 gen 💖🐣 💖🐷 💖n💔 💖🔁 loop 💖💖i 0💔💔 💖🐶 💖< i n💔 💖👣 💖🫳 i💔 💖loop 💖+ i 1💔💔💔 😪done💔💔💔💔💔 \
+            💖🚥 gen💔 💖🎬 gen 2💔 💖🚥 gen💔 💖gen💔 💖gen💔 💖🚥 gen💔 gen";
+        let got = input_and_go_all(input);
+        let got: Vec<String> = got[1..].iter().map(|r| r.as_ref().unwrap().to_string()).collect();
+        assert_eq!(got, vec!["🔑suspended", "0", "🔑suspended", "1", "done", "🔑dead", "<coroutine dead>"]);
+
+        let input = "💖🌹 sum 💖🐣 💖🐷 💖💔 💖🔁 loop 💖💖total 0💔💔 💖loop 💖+ total 💖🫳 total💔💔💔💔💔💔💔 \
+            💖🎬 sum💔 💖🎬 sum 5💔 💖🎬 sum 10💔";
+        let got = input_and_go_all(input);
+        assert_eq!(got[3], Ok(ast::NylispExpression::Number(15.0)));
+
+        let got = input_and_go_all("💖🌹 co 💖🐣 💖🐷 💖💔 💖🧨 😪boom💔💔💔💔 💖🎬 co💔 💖🚥 co💔 💖🎬 co💔 💖🫳 1💔");
+        assert!(got[1].is_err());
+        assert_eq!(got[2], Ok(ast::NylispExpression::Keyword("dead".into())));
+        assert_eq!(got[3].as_ref().unwrap_err().to_string(), "🎬 can't resume a dead coroutine");
+        assert_eq!(got[4].as_ref().unwrap_err().to_string(), "🫳 can only be used inside a coroutine");
+    }
+
+    // 📐 checks arguments and results at the call boundary and blames the right side
+    #[test]
+    fn eval_nylisp_contracts() {
+        let input = "💖🍳 positive? 💖n💔 💖> n 0💔💔 \
+            💖🍳 area 💖side 📐 number ❓ 💖scale 1💔 📐 positive?💔 📐 number 💬area of a square💬 💖* side side scale💔💔 \
+            💖area 3💔 💖area 💬hi💬💔 💖area 2 -1💔 \
+            💖🍳 broken 💖x 📐 any💔 📐 string x💔 💖broken 1💔 \
+            💖💖🐷 💖x 📐 list💔 📐 number x💔 😪💖1💔💔 \
+            💖🦺 👎💔 💖area 💬hi💬 2💔 💖broken 1💔 💖🦺 👍💔";
+        let got = input_and_go_all(input);
+        assert_eq!(got[2], Ok(ast::NylispExpression::Number(9.0)));
+        assert_eq!(got[3].as_ref().unwrap_err().to_string(), "area: argument side should be number, got hi (the top level, which passed side, is to blame)\n    in area");
+        assert_eq!(got[4].as_ref().unwrap_err().to_string(), "area: argument scale should be positive?, got -1 (the top level, which passed scale, is to blame)\n    in area");
+        assert_eq!(got[6].as_ref().unwrap_err().to_string(), "broken: result should be string, got 1 (broken is to blame)\n    in broken");
+        assert_eq!(got[7].as_ref().unwrap_err().to_string(), "🐷: result should be number, got 💖1 💔 (🐷 is to blame)");
+        assert!(got[9].is_err());
+        assert_eq!(got[10], Ok(ast::NylispExpression::Number(1.0)));
+
+        let got = input_and_go("💖🥅 💖💖🐷 💖x 📐 number💔 x💔 💬a💬💔 💖🎣 e 💖🆔 e💔💔💔");
+        assert_eq!(got[0], Ok(ast::NylispExpression::Keyword("contract".into())));
+        assert!(input_and_go("💖🐷 💖📐 number💔 1💔")[0].is_err());
+
+        // a named function calling with a bad argument is the one blamed
+        let input = "💖🍳 half 💖n 📐 number💔 💖/ n 2💔💔 💖🍳 halve-all 💖xs💔 💖half 💖🚗 xs💔💔💔 💖halve-all 😪💖a💔💔";
+        let got = input_and_go_all(input);
+        assert_eq!(got[2].as_ref().unwrap_err().to_string(), "half: argument n should be number, got a (halve-all, which passed n, is to blame)\n    in half\n    in halve-all");
+
+        // even inside a named function, the closure that makes the call is the one blamed
+        let input = "💖🍳 half 💖n 📐 number💔 💖/ n 2💔💔 💖🍳 halve-all 💖xs💔 💖💖🐷 💖x💔 💖half x💔💔 💖🚗 xs💔💔💔 💖halve-all 😪💖a💔💔";
+        let got = input_and_go_all(input);
+        assert_eq!(got[2].as_ref().unwrap_err().to_string(), "half: argument n should be number, got a (anonymous, which passed n, is to blame)\n    in half\n    in halve-all");
+
+        // the helpers behind 📐 check their arguments instead of indexing past them
+        let is_type_error = |got: Result<ast::NylispExpression, ast::NylispError>| matches!(
+            got.unwrap_err().to_expression(), ast::NylispExpression::Error { kind, .. } if kind == "type".into());
+        assert!(is_type_error(contract_type(vec![ast::NylispExpression::Number(1.0)])));
+        assert!(is_type_error(contract_blame(vec![])));
+    }
+
+    // 🦺 and set_contract_checking only switch the environment they are used in
+    #[test]
+    fn eval_nylisp_contract_switch() {
+        let program = |input: &str| -> Vec<ast::NylispExpression> {
+            let tokens = tokenizer::tokenizer::Tokenizer::new(input.to_string()).tokenize();
+            parser::parser::Parser::new().parse_programs(tokens).into_iter().map(|p| p.unwrap()).collect()
+        };
+        let checked = program("💖🍳 id 💖x 📐 number💔 x💔 💖id 💬a💬💔");
+        let env = environment::environment::builtin_env();
+        env.set_contract_checking(false);
+        let got = Evaluator::new(checked.clone()).eval_programs(&env);
+        assert_eq!(got[1], Ok(ast::NylispExpression::String("a".to_string())));
+        let got = Evaluator::new(checked.clone()).eval_programs(&environment::environment::builtin_env());
+        assert!(got[1].is_err());
+
+        let got = Evaluator::new(program("💖🦺 👎💔 💖🦺💔 💖🦺 1💔")).eval_programs(&env);
+        assert_eq!(got[1], Ok(ast::NylispExpression::Boolean(false)));
+        assert!(got[2].is_err());
+        assert!(Evaluator::new(checked).eval_programs(&environment::environment::builtin_env())[1].is_err());
+    }
+}
@@ -0,0 +1,217 @@
+use std::rc::Rc;
+use crate::ast::*;
+
+// what is left to do with the value of the expression being evaluated
+#[derive(Clone, Debug)]
+pub(crate) enum Frame {
+    // 🐶 waiting for its test
+    If {
+        then: ast::NylispExpression,
+        otherwise: ast::NylispExpression,
+        env: ast::Environment,
+    },
+    // 🌹 and 📝 waiting for the value to bind
+    Define {
+        name: ast::Symbol,
+        env: ast::Environment,
+    },
+    Set {
+        name: ast::Symbol,
+        env: ast::Environment,
+    },
+    // 👣 and closure bodies, exps[next..] still have to run
+    Sequence {
+        exps: Rc<Vec<ast::NylispExpression>>,
+        next: usize,
+        env: ast::Environment,
+    },
+    // a call, done holds the operator and the arguments evaluated so far
+    Args {
+        exps: Rc<Vec<ast::NylispExpression>>,
+        done: Vec<ast::NylispExpression>,
+        env: ast::Environment,
+    },
+    // 😎 and 😕 waiting for exps[next - 1]
+    Logic {
+        and: bool,
+        exps: Rc<Vec<ast::NylispExpression>>,
+        next: usize,
+        env: ast::Environment,
+    },
+    // 🚦 waiting for the test of clauses[next]
+    Cond {
+        clauses: Rc<Vec<ast::NylispExpression>>,
+        next: usize,
+        env: ast::Environment,
+    },
+    // 🔀 waiting for its key
+    Case {
+        clauses: Rc<Vec<ast::NylispExpression>>,
+        env: ast::Environment,
+    },
+    // body of a 🥅, errors unwinding through here run the handler
+    Try {
+        catch: Option<(ast::Symbol, Rc<Vec<ast::NylispExpression>>)>,
+        finally: Option<Rc<Vec<ast::NylispExpression>>>,
+        env: ast::Environment,
+    },
+    // 🎣 handler of a 🥅 that still has to run its 🧹
+    Finally {
+        exps: Rc<Vec<ast::NylispExpression>>,
+        env: ast::Environment,
+    },
+    // 🧹 is running, afterwards give back the value or raise the error from before it
+    Restore(ast::NylispExpression),
+    Reraise(ast::NylispError),
+    // body of a closure, errors passing through get its name added to the trace if it has one
+    Call(Option<ast::Symbol>),
+    // 🔖 waiting for the function to hand the continuation to
+    CallCc,
+    // delimits how much of the continuation 🪝 captures
+    Reset,
+    // a macro call waiting for its expansion, which then runs in env
+    Expand {
+        env: ast::Environment,
+    },
+    // 🔍 expanding until the head is no longer a macro
+    MacroExpand {
+        env: ast::Environment,
+    },
+    // ⏰ waiting for the promise to force
+    Force,
+    // body of a coroutine, 🫳 captures the frames above it and its value ends the coroutine
+    Coroutine(Rc<std::cell::RefCell<ast::CoroutineState>>),
+    // 🫳 waiting for the value to hand to 🎬
+    Yield,
+    // a promise's expression is running, its value gets remembered
+    Memo(Rc<std::cell::RefCell<ast::Lazy>>),
+    // 🦺 waiting for whether contracts should be checked from now on
+    Contracts {
+        env: ast::Environment,
+    },
+    // 🧩 waiting for the value to match
+    Match {
+        clauses: Rc<Vec<ast::NylispExpression>>,
+        env: ast::Environment,
+    },
+    // the ✅ guard of clauses[next], scope has the pattern's bindings
+    Guard {
+        subject: ast::NylispExpression,
+        clauses: Rc<Vec<ast::NylispExpression>>,
+        next: usize,
+        env: ast::Environment,
+        scope: ast::Environment,
+    },
+}
+
+// the rest of the computation as a linked list of frames, the top frame runs next.
+// frames are never changed once pushed, so a captured continuation can be resumed any number of times
+#[derive(Clone, Default)]
+pub struct Continuation {
+    top: Option<Rc<Link>>,
+}
+
+struct Link {
+    frame: Frame,
+    next: Option<Rc<Link>>,
+}
+
+impl Continuation {
+    pub(crate) fn push(&mut self, frame: Frame) {
+        let next = self.top.take();
+        self.top = Some(Rc::new(Link { frame, next }));
+    }
+
+    // frames still shared with a captured continuation are copied, the others are moved out
+    pub(crate) fn pop(&mut self) -> Option<Frame> {
+        let link = self.top.take()?;
+        match Rc::try_unwrap(link) {
+            Ok(link) => {
+                self.top = link.next;
+                Some(link.frame)
+            }
+            Err(link) => {
+                self.top = link.next.clone();
+                Some(link.frame.clone())
+            }
+        }
+    }
+
+    pub(crate) fn peek(&self) -> Option<&Frame> {
+        self.top.as_ref().map(|link| &link.frame)
+    }
+
+    // the closure whose body is running, the one that makes any call happening now. None at the top level
+    pub(crate) fn caller(&self) -> Option<Option<ast::Symbol>> {
+        let mut link = &self.top;
+        while let Some(current) = link {
+            if let Frame::Call(name) = current.frame {
+                return Some(name);
+            }
+            link = &current.next;
+        }
+        None
+    }
+
+    // top to bottom
+    pub(crate) fn frames(&self) -> Vec<Frame> {
+        let mut frames = Vec::new();
+        let mut link = &self.top;
+        while let Some(current) = link {
+            frames.push(current.frame.clone());
+            link = &current.next;
+        }
+        frames
+    }
+
+    // moves the frames above the nearest boundary, a 🚧 or a coroutine, into their own continuation,
+    // the boundary itself stays. None, and nothing is moved, when there is no boundary
+    pub(crate) fn split_at(&mut self, boundary: fn(&Frame) -> bool) -> Option<Continuation> {
+        let mut link = &self.top;
+        loop {
+            match link {
+                Some(current) if boundary(&current.frame) => break,
+                Some(current) => link = &current.next,
+                None => return None,
+            }
+        }
+        let mut captured = Vec::new();
+        while !self.peek().is_some_and(boundary) {
+            captured.extend(self.pop());
+        }
+        let mut segment = Continuation::default();
+        for frame in captured.into_iter().rev() {
+            segment.push(frame);
+        }
+        Some(segment)
+    }
+}
+
+// deep recursion builds long chains, dropping them link by link keeps the native stack flat
+impl Drop for Continuation {
+    fn drop(&mut self) {
+        let mut top = self.top.take();
+        while let Some(link) = top {
+            match Rc::try_unwrap(link) {
+                Ok(mut link) => top = link.next.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for Continuation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "<continuation>")
+    }
+}
+
+impl PartialEq for Continuation {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.top, &other.top) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
@@ -1 +1,2 @@
-pub mod evaluation;
\ No newline at end of file
+pub mod evaluation;
+pub mod frame;
@@ -0,0 +1,489 @@
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use crate::ast;
+use crate::tokenizer;
+
+// what a pattern variable matched, variables under 🔂 match once per repetition
+#[derive(Clone, Debug)]
+enum Binding {
+    One(ast::ast::NylispExpression),
+    Seq(Vec<Binding>),
+}
+
+// rewrites 🧬 macro uses into plain code before anything is evaluated
+pub struct Expander {
+    env: ast::ast::Environment,
+}
+
+impl Expander {
+    pub fn new(env: &ast::ast::Environment) -> Expander {
+        Expander { env: env.clone() }
+    }
+
+    pub fn expand_programs(&self, programs: &[ast::ast::NylispExpression]) -> Vec<Result<ast::ast::NylispExpression, ast::ast::NylispError>> {
+        programs.iter().map(|program| self.expand_program(program)).collect()
+    }
+
+    // a 🧬 program is bound in the environment right away and leaves 🫥 behind, so later programs can use it.
+    // one inside other code is bound by the evaluator in the scope it runs in, if it runs at all
+    fn expand_program(&self, exp: &ast::ast::NylispExpression) -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+        match exp {
+            ast::ast::NylispExpression::List(l) if matches!(l.first(), Some(ast::ast::NylispExpression::Symbol(s)) if s.as_str() == tokenizer::tokenizer::DEFINE_SYNTAX) => {
+                let (name, syntax) = self.define_syntax(&l[1..])?;
+                self.env.define(name, syntax);
+                Ok(ast::ast::NylispExpression::Nil)
+            }
+            _ => self.expand(exp),
+        }
+    }
+
+    pub fn expand(&self, exp: &ast::ast::NylispExpression) -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+        self.expand_in(exp, &HashSet::new())
+    }
+
+    // locals are the names the code around exp binds, they hide macros of the same name
+    fn expand_in(&self, exp: &ast::ast::NylispExpression, locals: &HashSet<ast::ast::Symbol>) -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+        let list = match exp {
+            ast::ast::NylispExpression::List(l) if !l.is_empty() => l,
+            _ => return Ok(exp.clone()),
+        };
+        if let ast::ast::NylispExpression::Symbol(s) = &list[0] {
+            if !locals.contains(s) {
+                match s.as_str() {
+                    // templates are data, and a nested 🧬 is left for the evaluator
+                    tokenizer::tokenizer::QUASIQUOTE | tokenizer::tokenizer::DEFINE_SYNTAX => return Ok(exp.clone()),
+                    _ => {}
+                }
+                if let Some(syntax @ ast::ast::NylispExpression::SyntaxRules { .. }) = ast::ast::get_symbol(*s, &self.env) {
+                    let expanded = self.transcribe(&syntax, exp)?;
+                    return self.expand_in(&expanded, locals);
+                }
+                if let Some(expanded) = self.expand_binding_form(s.as_str(), list, locals)? {
+                    return Ok(expanded);
+                }
+            }
+        }
+
+        Ok(ast::ast::NylispExpression::List(self.expand_body(list, locals)?))
+    }
+
+    // 🐷 parameters, 🍙 style binding names and 🧩 patterns aren't code, what they bind is local to the body
+    fn expand_binding_form(&self, form: &str, list: &[ast::ast::NylispExpression], locals: &HashSet<ast::ast::Symbol>) -> Result<Option<ast::ast::NylispExpression>, ast::ast::NylispError> {
+        let mut inner = locals.clone();
+        let mut expanded = list.to_vec();
+        let body = match form {
+            tokenizer::tokenizer::CLOSURE if list.len() > 1 => {
+                self.param_binders(&list[1], &mut inner);
+                2
+            }
+            tokenizer::tokenizer::DEFUN | tokenizer::tokenizer::DEFMACRO if list.len() > 2 => {
+                self.symbol_binders(&list[1], &mut inner);
+                self.param_binders(&list[2], &mut inner);
+                3
+            }
+            tokenizer::tokenizer::SCOPED_LET | tokenizer::tokenizer::SEQUENTIAL_LET | tokenizer::tokenizer::RECURSIVE_LET if list.len() > 1 => {
+                expanded[1] = self.expand_let_bindings(form, &list[1], &mut inner)?;
+                2
+            }
+            tokenizer::tokenizer::NAMED_LET if list.len() > 2 => {
+                self.symbol_binders(&list[1], &mut inner);
+                expanded[2] = self.expand_let_bindings(form, &list[2], &mut inner)?;
+                3
+            }
+            tokenizer::tokenizer::CATCH | tokenizer::tokenizer::SHIFT if list.len() > 1 => {
+                self.symbol_binders(&list[1], &mut inner);
+                2
+            }
+            tokenizer::tokenizer::MATCH if list.len() > 1 => {
+                expanded[1] = self.expand_in(&list[1], locals)?;
+                for clause in expanded[2..].iter_mut() {
+                    if let ast::ast::NylispExpression::List(c) = clause {
+                        if !c.is_empty() {
+                            let mut bound = locals.clone();
+                            self.pattern_binders(&c[0], &mut bound);
+                            let mut c_expanded = vec![c[0].clone()];
+                            c_expanded.extend(self.expand_body(&c[1..], &bound)?);
+                            *clause = ast::ast::NylispExpression::List(c_expanded);
+                        }
+                    }
+                }
+                return Ok(Some(ast::ast::NylispExpression::List(expanded)));
+            }
+            _ => return Ok(None),
+        };
+        expanded.truncate(body);
+        expanded.extend(self.expand_body(&list[body..], &inner)?);
+
+        Ok(Some(ast::ast::NylispExpression::List(expanded)))
+    }
+
+    // only the values are code, 🍢 values see the names before them and 🍥 values see all of them
+    fn expand_let_bindings(&self, form: &str, bindings: &ast::ast::NylispExpression, inner: &mut HashSet<ast::ast::Symbol>) -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+        let l = match bindings {
+            ast::ast::NylispExpression::List(l) => l,
+            _ => return Ok(bindings.clone()),
+        };
+        let outer = inner.clone();
+        if form == tokenizer::tokenizer::RECURSIVE_LET {
+            self.let_binders(bindings, inner);
+        }
+        let mut expanded = Vec::new();
+        for binding in l.iter() {
+            match binding {
+                ast::ast::NylispExpression::List(b) if !b.is_empty() => {
+                    let scope = if form == tokenizer::tokenizer::SCOPED_LET || form == tokenizer::tokenizer::NAMED_LET { &outer } else { &*inner };
+                    let mut b_expanded = vec![b[0].clone()];
+                    for value in b[1..].iter() {
+                        b_expanded.push(self.expand_in(value, scope)?);
+                    }
+                    self.pattern_binders(&b[0], inner);
+                    expanded.push(ast::ast::NylispExpression::List(b_expanded));
+                }
+                _ => expanded.push(binding.clone()),
+            }
+        }
+
+        Ok(ast::ast::NylispExpression::List(expanded))
+    }
+
+    // names 🌹, 🍳, 🧙 or 🧬 define directly in exps are local to all of them
+    fn expand_body(&self, exps: &[ast::ast::NylispExpression], locals: &HashSet<ast::ast::Symbol>) -> Result<Vec<ast::ast::NylispExpression>, ast::ast::NylispError> {
+        let mut inner = locals.clone();
+        for exp in exps.iter() {
+            if let ast::ast::NylispExpression::List(l) = exp {
+                if let (Some(ast::ast::NylispExpression::Symbol(s)), Some(name)) = (l.first(), l.get(1)) {
+                    match s.as_str() {
+                        tokenizer::tokenizer::VAR | tokenizer::tokenizer::DEFUN | tokenizer::tokenizer::DEFMACRO | tokenizer::tokenizer::DEFINE_SYNTAX if !locals.contains(s) => {
+                            self.symbol_binders(name, &mut inner)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        exps.iter().map(|e| self.expand_in(e, &inner)).collect()
+    }
+
+    // 💖🧬 name 💖literals...💔 💖pattern template💔...💔
+    pub fn define_syntax(&self, args: &[ast::ast::NylispExpression]) -> Result<(ast::ast::Symbol, ast::ast::NylispExpression), ast::ast::NylispError> {
+        if args.len() < 3 {
+            return Err(ast::ast::NylispError::Because(format!("🧬 requires a name, a list of literals and at least one rule, got {} arguments", args.len())));
+        }
+        let name = match &args[0] {
+            ast::ast::NylispExpression::Symbol(s) => *s,
+            _ => return Err(ast::ast::NylispError::Because(format!("🧬 requires a symbol as its name, got {:?}", args[0]))),
+        };
+        let literals = match &args[1] {
+            ast::ast::NylispExpression::List(l) => l.iter().map(|literal| match literal {
+                ast::ast::NylispExpression::Symbol(s) => Ok(*s),
+                _ => Err(ast::ast::NylispError::Because(format!("🧬 literals should be symbols, got {:?}", literal))),
+            }).collect::<Result<Vec<ast::ast::Symbol>, ast::ast::NylispError>>()?,
+            _ => return Err(ast::ast::NylispError::Because(format!("🧬 requires a list of literals, got {:?}", args[1]))),
+        };
+        let mut rules = Vec::new();
+        for rule in args[2..].iter() {
+            match rule {
+                ast::ast::NylispExpression::List(r) if r.len() == 2 && r[0].unroll().is_some() => rules.push((r[0].clone(), r[1].clone())),
+                _ => return Err(ast::ast::NylispError::Because(format!("🧬 rules are a list of a pattern and a template, got {}", rule))),
+            }
+        }
+
+        Ok((name, ast::ast::NylispExpression::SyntaxRules { name, literals: Rc::new(literals), rules: Rc::new(rules) }))
+    }
+
+    // first rule whose pattern matches wins, the macro keyword itself is never matched
+    pub fn transcribe(&self, syntax: &ast::ast::NylispExpression, form: &ast::ast::NylispExpression) -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+        let (name, literals, rules) = match syntax {
+            ast::ast::NylispExpression::SyntaxRules { name, literals, rules } => (name, literals, rules),
+            _ => return Err(ast::ast::NylispError::Because(format!("expected 🧬 rules, got {}", syntax))),
+        };
+        let args = self.rest_of(form);
+        for (pattern, template) in rules.iter() {
+            let mut bindings: HashMap<ast::ast::Symbol, Binding> = HashMap::new();
+            if self.match_pattern(&self.rest_of(pattern), &args, literals, &mut bindings) {
+                let mut renames: HashMap<ast::ast::Symbol, ast::ast::Symbol> = HashMap::new();
+                let expanded = self.instantiate(template, &bindings, &mut renames)?;
+                return Ok(self.restore_free(&expanded, &renames));
+            }
+        }
+
+        Err(ast::ast::NylispError::Because(format!("{} has no rule matching {}", name, form)))
+    }
+
+    // everything after the head of a list or dotted list
+    fn rest_of(&self, exp: &ast::ast::NylispExpression) -> ast::ast::NylispExpression {
+        match exp.unroll() {
+            Some((items, tail)) if !items.is_empty() => self.rebuild(items[1..].to_vec(), tail),
+            _ => ast::ast::NylispExpression::List(vec![]),
+        }
+    }
+
+    fn rebuild(&self, items: Vec<ast::ast::NylispExpression>, tail: Option<ast::ast::NylispExpression>) -> ast::ast::NylispExpression {
+        let mut pair = match tail {
+            Some(tail) => tail,
+            None => return ast::ast::NylispExpression::List(items),
+        };
+        for item in items.into_iter().rev() {
+            pair = ast::ast::NylispExpression::Pair(Rc::new(item), Rc::new(pair));
+        }
+        match pair.proper_list() {
+            Some(items) => ast::ast::NylispExpression::List(items),
+            None => pair,
+        }
+    }
+
+    fn is_ellipsis(&self, exp: Option<&ast::ast::NylispExpression>) -> bool {
+        matches!(exp, Some(ast::ast::NylispExpression::Symbol(s)) if s.as_str() == tokenizer::tokenizer::ELLIPSIS)
+    }
+
+    // 🤷 matches anything, literals match themselves and other symbols bind what they match
+    fn match_pattern(
+        &self,
+        pattern: &ast::ast::NylispExpression,
+        form: &ast::ast::NylispExpression,
+        literals: &[ast::ast::Symbol],
+        bindings: &mut HashMap<ast::ast::Symbol, Binding>,
+    ) -> bool {
+        match pattern {
+            ast::ast::NylispExpression::Symbol(s) if s.as_str() == tokenizer::tokenizer::ELSE => true,
+            ast::ast::NylispExpression::Symbol(s) if literals.contains(s) => form == pattern,
+            ast::ast::NylispExpression::Symbol(s) => {
+                bindings.insert(*s, Binding::One(form.clone()));
+                true
+            }
+            ast::ast::NylispExpression::List(_) | ast::ast::NylispExpression::Pair(_, _) | ast::ast::NylispExpression::Tail(_) => {
+                let ((p_items, p_tail), (f_items, f_tail)) = match (pattern.unroll(), form.unroll()) {
+                    (Some(p), Some(f)) => (p, f),
+                    _ => return false,
+                };
+                let ellipsis = (0..p_items.len()).find(|i| self.is_ellipsis(p_items.get(i + 1)));
+                let (before, repeated, after) = match ellipsis {
+                    Some(i) => (&p_items[..i], Some(&p_items[i]), &p_items[i + 2..]),
+                    None => (&p_items[..], None, &p_items[p_items.len()..]),
+                };
+                let fixed = before.len() + after.len();
+                if f_items.len() < fixed || (repeated.is_none() && p_tail.is_none() && f_items.len() != fixed) {
+                    return false;
+                }
+
+                for (p, f) in before.iter().zip(f_items.iter()) {
+                    if !self.match_pattern(p, f, literals, bindings) {
+                        return false;
+                    }
+                }
+                let mut next = before.len();
+                if let Some(repeated) = repeated {
+                    let count = f_items.len() - fixed;
+                    let vars = self.pattern_vars(repeated, literals);
+                    let mut seqs: HashMap<ast::ast::Symbol, Vec<Binding>> = vars.iter().map(|v| (*v, Vec::new())).collect();
+                    for f in f_items[next..next + count].iter() {
+                        let mut one: HashMap<ast::ast::Symbol, Binding> = HashMap::new();
+                        if !self.match_pattern(repeated, f, literals, &mut one) {
+                            return false;
+                        }
+                        for (var, seq) in seqs.iter_mut() {
+                            seq.push(one.remove(var).unwrap());
+                        }
+                    }
+                    bindings.extend(seqs.into_iter().map(|(var, seq)| (var, Binding::Seq(seq))));
+                    next += count;
+                }
+                for (p, f) in after.iter().zip(f_items[next..].iter()) {
+                    if !self.match_pattern(p, f, literals, bindings) {
+                        return false;
+                    }
+                }
+                next += after.len();
+                match p_tail {
+                    Some(p_tail) => {
+                        let rest = self.rebuild(f_items[next..].to_vec(), f_tail);
+                        self.match_pattern(&p_tail, &rest, literals, bindings)
+                    }
+                    None => f_tail.is_none(),
+                }
+            }
+            _ => form == pattern,
+        }
+    }
+
+    fn pattern_vars(&self, pattern: &ast::ast::NylispExpression, literals: &[ast::ast::Symbol]) -> Vec<ast::ast::Symbol> {
+        match pattern {
+            ast::ast::NylispExpression::Symbol(s) if s.as_str() == tokenizer::tokenizer::ELSE || s.as_str() == tokenizer::tokenizer::ELLIPSIS || literals.contains(s) => vec![],
+            ast::ast::NylispExpression::Symbol(s) => vec![*s],
+            _ => match pattern.unroll() {
+                Some((items, tail)) => items.iter().chain(tail.iter()).flat_map(|p| self.pattern_vars(p, literals)).collect(),
+                None => vec![],
+            },
+        }
+    }
+
+    // pattern variables are replaced by what they matched, every other symbol gets a fresh name
+    fn instantiate(
+        &self,
+        template: &ast::ast::NylispExpression,
+        bindings: &HashMap<ast::ast::Symbol, Binding>,
+        renames: &mut HashMap<ast::ast::Symbol, ast::ast::Symbol>,
+    ) -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+        match template {
+            ast::ast::NylispExpression::Symbol(s) => match bindings.get(s) {
+                Some(Binding::One(e)) => Ok(e.clone()),
+                Some(Binding::Seq(_)) => Err(ast::ast::NylispError::Because(format!("pattern variable {} has to be followed by {}", s, tokenizer::tokenizer::ELLIPSIS))),
+                None => Ok(ast::ast::NylispExpression::Symbol(*renames.entry(*s).or_insert_with(|| ast::ast::Symbol::gensym(s.as_str())))),
+            },
+            ast::ast::NylispExpression::Quote(inner) => Ok(ast::ast::NylispExpression::Quote(Rc::new(self.instantiate(inner, bindings, renames)?))),
+            ast::ast::NylispExpression::List(_) | ast::ast::NylispExpression::Pair(_, _) | ast::ast::NylispExpression::Tail(_) => {
+                let (items, tail) = template.unroll().unwrap();
+                let mut result = Vec::new();
+                let mut i = 0;
+                while i < items.len() {
+                    if !self.is_ellipsis(items.get(i + 1)) {
+                        result.push(self.instantiate(&items[i], bindings, renames)?);
+                        i += 1;
+                        continue;
+                    }
+                    // the element is repeated once for each match of the 🔂 variables inside it
+                    let vars: Vec<(ast::ast::Symbol, &Vec<Binding>)> = self.pattern_vars(&items[i], &[]).into_iter().filter_map(|v| match bindings.get(&v) {
+                        Some(Binding::Seq(seq)) => Some((v, seq)),
+                        _ => None,
+                    }).collect();
+                    let count = match vars.first() {
+                        Some((_, seq)) => seq.len(),
+                        None => return Err(ast::ast::NylispError::Because(format!("{} follows {} which has no repeated pattern variables", tokenizer::tokenizer::ELLIPSIS, items[i]))),
+                    };
+                    if vars.iter().any(|(_, seq)| seq.len() != count) {
+                        return Err(ast::ast::NylispError::Because(format!("pattern variables in {} matched different numbers of elements", items[i])));
+                    }
+                    for j in 0..count {
+                        let mut inner = bindings.clone();
+                        for (var, seq) in vars.iter() {
+                            inner.insert(*var, seq[j].clone());
+                        }
+                        result.push(self.instantiate(&items[i], &inner, renames)?);
+                    }
+                    i += 2;
+                }
+                let tail = match tail {
+                    Some(tail) => Some(self.instantiate(&tail, bindings, renames)?),
+                    None => None,
+                };
+                Ok(self.rebuild(result, tail))
+            }
+            _ => Ok(template.clone()),
+        }
+    }
+
+    // fresh names only stay where the template itself binds them, so they can't capture the user's variables.
+    // the rest go back to their own names and mean the special form, builtin or global they were written as
+    fn restore_free(&self, exp: &ast::ast::NylispExpression, renames: &HashMap<ast::ast::Symbol, ast::ast::Symbol>) -> ast::ast::NylispExpression {
+        let aliases: HashMap<ast::ast::Symbol, ast::ast::Symbol> = renames.iter().map(|(name, alias)| (*alias, *name)).collect();
+        let mut bound: HashSet<ast::ast::Symbol> = HashSet::new();
+        self.binders(exp, &aliases, &mut bound);
+        let free: HashMap<ast::ast::Symbol, ast::ast::Symbol> = aliases.into_iter().filter(|(alias, _)| !bound.contains(alias)).collect();
+        self.rename(exp, &free)
+    }
+
+    fn rename(&self, exp: &ast::ast::NylispExpression, names: &HashMap<ast::ast::Symbol, ast::ast::Symbol>) -> ast::ast::NylispExpression {
+        match exp {
+            ast::ast::NylispExpression::Symbol(s) => ast::ast::NylispExpression::Symbol(*names.get(s).unwrap_or(s)),
+            ast::ast::NylispExpression::Quote(inner) => ast::ast::NylispExpression::Quote(Rc::new(self.rename(inner, names))),
+            ast::ast::NylispExpression::List(_) | ast::ast::NylispExpression::Pair(_, _) | ast::ast::NylispExpression::Tail(_) => {
+                let (items, tail) = exp.unroll().unwrap();
+                self.rebuild(items.iter().map(|e| self.rename(e, names)).collect(), tail.map(|t| self.rename(&t, names)))
+            }
+            _ => exp.clone(),
+        }
+    }
+
+    // names bound by the binding forms anywhere inside exp, the form names themselves may still be renamed
+    fn binders(&self, exp: &ast::ast::NylispExpression, aliases: &HashMap<ast::ast::Symbol, ast::ast::Symbol>, bound: &mut HashSet<ast::ast::Symbol>) {
+        let list = match exp {
+            ast::ast::NylispExpression::List(l) => l,
+            ast::ast::NylispExpression::Quote(inner) => return self.binders(inner, aliases, bound),
+            _ => return,
+        };
+        if let Some(ast::ast::NylispExpression::Symbol(s)) = list.first() {
+            match aliases.get(s).unwrap_or(s).as_str() {
+                tokenizer::tokenizer::CLOSURE if list.len() > 1 => self.param_binders(&list[1], bound),
+                tokenizer::tokenizer::DEFUN | tokenizer::tokenizer::DEFMACRO if list.len() > 2 => self.param_binders(&list[2], bound),
+                tokenizer::tokenizer::SCOPED_LET | tokenizer::tokenizer::SEQUENTIAL_LET | tokenizer::tokenizer::RECURSIVE_LET if list.len() > 1 => {
+                    self.let_binders(&list[1], bound)
+                }
+                tokenizer::tokenizer::NAMED_LET if list.len() > 2 => {
+                    self.symbol_binders(&list[1], bound);
+                    self.let_binders(&list[2], bound);
+                }
+                tokenizer::tokenizer::CATCH | tokenizer::tokenizer::SHIFT if list.len() > 1 => self.symbol_binders(&list[1], bound),
+                tokenizer::tokenizer::MATCH => {
+                    for clause in list.iter().skip(2) {
+                        if let ast::ast::NylispExpression::List(c) = clause {
+                            if let Some(pattern) = c.first() {
+                                self.pattern_binders(pattern, bound);
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        for e in list.iter() {
+            self.binders(e, aliases, bound);
+        }
+    }
+
+    // required parameters, then the names of ❓ and 🔐 parameters but not their defaults
+    fn param_binders(&self, params: &ast::ast::NylispExpression, bound: &mut HashSet<ast::ast::Symbol>) {
+        let (items, tail) = match params.unroll() {
+            Some(unrolled) => unrolled,
+            None => return self.symbol_binders(params, bound),
+        };
+        let mut section = false;
+        let mut items = items.iter();
+        while let Some(item) = items.next() {
+            match item {
+                // the contract after 📐 is an expression, not a parameter
+                ast::ast::NylispExpression::Symbol(s) if s.as_str() == tokenizer::tokenizer::CONTRACT => {
+                    items.next();
+                }
+                ast::ast::NylispExpression::Symbol(s) if s.as_str() == tokenizer::tokenizer::OPTIONAL || s.as_str() == tokenizer::tokenizer::KEYS => section = true,
+                ast::ast::NylispExpression::List(l) if section && !l.is_empty() => self.symbol_binders(&l[0], bound),
+                _ => self.pattern_binders(item, bound),
+            }
+        }
+        if let Some(tail) = tail {
+            self.symbol_binders(&tail, bound);
+        }
+    }
+
+    // every symbol in a 🧩 pattern or a destructuring parameter binds, except inside 😪
+    fn pattern_binders(&self, pattern: &ast::ast::NylispExpression, bound: &mut HashSet<ast::ast::Symbol>) {
+        match pattern {
+            ast::ast::NylispExpression::Symbol(_) => self.symbol_binders(pattern, bound),
+            ast::ast::NylispExpression::List(_) | ast::ast::NylispExpression::Pair(_, _) | ast::ast::NylispExpression::Tail(_) => {
+                if let Some((items, tail)) = pattern.unroll() {
+                    for p in items.iter().chain(tail.iter()) {
+                        self.pattern_binders(p, bound);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn let_binders(&self, bindings: &ast::ast::NylispExpression, bound: &mut HashSet<ast::ast::Symbol>) {
+        if let ast::ast::NylispExpression::List(l) = bindings {
+            for binding in l.iter() {
+                if let ast::ast::NylispExpression::List(b) = binding {
+                    if let Some(name) = b.first() {
+                        self.pattern_binders(name, bound);
+                    }
+                }
+            }
+        }
+    }
+
+    fn symbol_binders(&self, exp: &ast::ast::NylispExpression, bound: &mut HashSet<ast::ast::Symbol>) {
+        if let ast::ast::NylispExpression::Symbol(s) = exp {
+            bound.insert(*s);
+        }
+    }
+}
@@ -6,6 +6,8 @@ mod tokenizer;
 pub mod ast;
 pub mod environment;
 mod evaluation;
+mod expander;
+pub mod module;
 
 // export ast, environment to lib.rs
 pub use ast::*;
@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+use crate::ast;
+
+// where 📥 gets the source of a module from, the CLI reads files and the wasm build a map js handed over
+pub trait ModuleLoader {
+    fn load(&self, name: &str) -> Result<String, ast::ast::NylispError>;
+}
+
+// name.nylisp in the root directory
+pub struct FileLoader {
+    root: PathBuf,
+}
+
+impl FileLoader {
+    pub fn new(root: impl Into<PathBuf>) -> FileLoader {
+        FileLoader { root: root.into() }
+    }
+}
+
+impl ModuleLoader for FileLoader {
+    fn load(&self, name: &str) -> Result<String, ast::ast::NylispError> {
+        let path = self.root.join(format!("{}.nylisp", name));
+        std::fs::read_to_string(&path).map_err(|e| ast::ast::NylispError::of_kind(
+            "module",
+            format!("can't read module {} from {}: {}", name, path.display(), e),
+        ))
+    }
+}
+
+// module sources by name, kept in memory
+pub struct MapLoader {
+    sources: HashMap<String, String>,
+}
+
+impl MapLoader {
+    pub fn new(sources: HashMap<String, String>) -> MapLoader {
+        MapLoader { sources }
+    }
+}
+
+impl ModuleLoader for MapLoader {
+    fn load(&self, name: &str) -> Result<String, ast::ast::NylispError> {
+        match self.sources.get(name) {
+            Some(source) => Ok(source.clone()),
+            None => Err(ast::ast::NylispError::of_kind("module", format!("no module named {}", name))),
+        }
+    }
+}
+
+// a loaded module, its own environment and the names 📤 made public
+#[derive(Clone)]
+pub(crate) struct Module {
+    pub(crate) env: ast::ast::Environment,
+    pub(crate) exports: Vec<ast::ast::Symbol>,
+}
+
+#[derive(Default)]
+pub struct Modules {
+    pub(crate) loader: Option<Rc<dyn ModuleLoader>>,
+    loaded: HashMap<String, Module>,
+    // modules whose source is running right now, innermost last, with what they exported so far
+    loading: Vec<(String, Vec<ast::ast::Symbol>)>,
+    // 🔑as names from 📥, shared by the whole program like the modules themselves
+    aliases: HashMap<String, String>,
+}
+
+impl Modules {
+    // a loaded module by its name or an alias of it
+    fn resolve(&self, namespace: &str) -> Option<&Module> {
+        let name = self.aliases.get(namespace).map(String::as_str).unwrap_or(namespace);
+        self.loaded.get(name)
+    }
+
+    pub(crate) fn alias(&mut self, alias: &str, module: &str) {
+        self.aliases.insert(alias.to_string(), module.to_string());
+    }
+
+    // what 📋 lists, in the order they were exported
+    pub(crate) fn public_bindings(&self, namespace: &str) -> Option<Vec<ast::ast::Symbol>> {
+        self.resolve(namespace).map(|module| module.exports.clone())
+    }
+
+    // 📤 inside the module being loaded
+    pub(crate) fn export(&mut self, names: &[ast::ast::Symbol]) -> Result<(), ast::ast::NylispError> {
+        match self.loading.last_mut() {
+            Some((_, exports)) => {
+                exports.extend(names.iter().filter(|name| !exports.contains(name)).copied().collect::<Vec<_>>());
+                Ok(())
+            }
+            None => Err(ast::ast::NylispError::Because("📤 can only be used in a module".to_string())),
+        }
+    }
+}
+
+// value of module/name when name is exported by module, or an alias of it
+pub(crate) fn qualified(key: &str, env: &ast::ast::Environment) -> Option<ast::ast::NylispExpression> {
+    let (namespace, name) = key.rsplit_once('/')?;
+    if namespace.is_empty() || name.is_empty() {
+        return None;
+    }
+    // an export was interned when the module defined it, a name nobody interned can't be one
+    let name = ast::ast::Symbol::lookup(name)?;
+    let modules = env.modules.borrow();
+    modules.resolve(namespace)
+        .filter(|module| module.exports.contains(&name))
+        .and_then(|module| module.env.data.borrow().get(&name).map(|value| module.env.load(value)))
+}
+
+// the module called name, its source runs once in an environment of its own the first time it is imported
+pub(crate) fn import(env: &ast::ast::Environment, name: &str) -> Result<Module, ast::ast::NylispError> {
+    let loader = {
+        let modules = env.modules.borrow();
+        if let Some(module) = modules.loaded.get(name) {
+            return Ok(module.clone());
+        }
+        if let Some(start) = modules.loading.iter().position(|(loading, _)| loading == name) {
+            let mut cycle: Vec<&str> = modules.loading[start..].iter().map(|(loading, _)| loading.as_str()).collect();
+            cycle.push(name);
+            return Err(ast::ast::NylispError::of_kind("module", format!("📥 import cycle {}", cycle.join(" → "))));
+        }
+        match &modules.loader {
+            Some(loader) => loader.clone(),
+            None => return Err(ast::ast::NylispError::of_kind("module", format!("📥 has no module loader to find {}", name))),
+        }
+    };
+    let source = loader.load(name)?;
+
+    env.modules.borrow_mut().loading.push((name.to_string(), vec![]));
+    let module_env = crate::environment::environment::builtin_env_sharing(env.modules.clone());
+    // a module starts out checking contracts like whoever imported it, later 🦺 on either side stays on that side
+    module_env.set_contract_checking(env.contract_checking());
+    let result = run(&source, &module_env);
+    let (_, exports) = env.modules.borrow_mut().loading.pop().unwrap();
+    if let Err(e) = result {
+        return Err(ast::ast::NylispError::of_kind("module", format!("module {} failed to load: {}", name, e)));
+    }
+    if let Some(missing) = exports.iter().find(|export| module_env.data.borrow().get(export).is_none()) {
+        return Err(ast::ast::NylispError::of_kind("module", format!("module {} exports {} but doesn't define it", name, missing)));
+    }
+
+    let module = Module { env: module_env, exports };
+    env.modules.borrow_mut().loaded.insert(name.to_string(), module.clone());
+    Ok(module)
+}
+
+fn run(source: &str, env: &ast::ast::Environment) -> Result<(), ast::ast::NylispError> {
+    let tokens = crate::tokenizer::tokenizer::Tokenizer::new(source.to_string()).tokenize();
+    if tokens.is_empty() {
+        return Ok(());
+    }
+    let forms = crate::parser::parser::Parser::new().parse_programs(tokens).into_iter().collect::<Result<Vec<_>, _>>()?;
+    for result in crate::evaluation::evaluation::Evaluator::new(forms).eval_programs(env) {
+        result?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_with(modules: &[(&str, &str)], input: &str) -> Vec<Result<ast::ast::NylispExpression, ast::ast::NylispError>> {
+        let sources = modules.iter().map(|(name, source)| (name.to_string(), source.to_string())).collect();
+        let env = crate::environment::environment::builtin_env();
+        env.set_module_loader(Rc::new(MapLoader::new(sources)));
+        let tokens = crate::tokenizer::tokenizer::Tokenizer::new(input.to_string()).tokenize();
+        let forms = crate::parser::parser::Parser::new().parse_programs(tokens).into_iter().map(|f| f.unwrap()).collect();
+        crate::evaluation::evaluation::Evaluator::new(forms).eval_programs(&env)
+    }
+
+    const GEOMETRY: &str = "💖📤 area perimeter💔 \
+        💖🍳 square 💖x💔 💖* x x💔💔 \
+        💖🍳 area 💖side💔 💖square side💔💔 \
+        💖🍳 perimeter 💖side💔 💖* 4 side💔💔";
+
+    #[test]
+    fn import_exports() {
+        let got = run_with(&[("geometry", GEOMETRY)], "💖📥 geometry💔 💖area 3💔 💖perimeter 3💔 square");
+        assert_eq!(got[1], Ok(ast::ast::NylispExpression::Number(9.0)));
+        assert_eq!(got[2], Ok(ast::ast::NylispExpression::Number(12.0)));
+        // only exports come over, the module's helpers stay in its own environment
+        assert!(got[3].is_err());
+    }
+
+    #[test]
+    fn import_options() {
+        let input = "💖📥 geometry 🔑only 💖area💔 🔑prefix geo-💔 💖geo-area 2💔 \
+            💖📥 geometry 🔑rename 💖💖perimeter around💔💔💔 💖around 2💔 \
+            💖📥 geometry 🔑only 💖square💔💔";
+        let got = run_with(&[("geometry", GEOMETRY)], input);
+        assert_eq!(got[1], Ok(ast::ast::NylispExpression::Number(4.0)));
+        assert_eq!(got[3], Ok(ast::ast::NylispExpression::Number(8.0)));
+        assert_eq!(got[4].as_ref().unwrap_err().to_string(), "module geometry doesn't export square");
+    }
+
+    #[test]
+    fn import_errors() {
+        let a = "💖📥 b💔 💖📤 x💔 💖🌹 x 1💔";
+        let b = "💖📥 a💔";
+        let got = run_with(&[("a", a), ("b", b)], "💖📥 a💔");
+        assert!(got[0].as_ref().unwrap_err().to_string().contains("📥 import cycle a → b → a"));
+
+        let got = run_with(&[("bad", "💖📤 missing💔")], "💖📥 bad💔 💖📥 nowhere💔 💖📤 x💔");
+        assert_eq!(got[0].as_ref().unwrap_err().to_string(), "module bad exports missing but doesn't define it");
+        assert_eq!(got[1].as_ref().unwrap_err().to_string(), "no module named nowhere");
+        assert_eq!(got[2].as_ref().unwrap_err().to_string(), "📤 can only be used in a module");
+    }
+
+    // 🦺 in the importing program doesn't reach into a module that is already loaded
+    #[test]
+    fn module_contracts() {
+        let checked = "💖📤 id💔 💖🍳 id 💖x 📐 number💔 x💔";
+        let got = run_with(&[("checked", checked)], "💖📥 checked💔 💖🦺 👎💔 💖id 💬a💬💔 💖🍳 mine 💖x 📐 number💔 x💔 💖mine 💬a💬💔");
+        assert!(got[2].is_err());
+        assert_eq!(got[4], Ok(ast::ast::NylispExpression::String("a".to_string())));
+    }
+
+    #[test]
+    fn qualified_names() {
+        let input = "💖📥 geometry 🔑as geo💔 💖geo/area 3💔 💖geometry/perimeter 1💔 💖📋 geo💔 \
+            area geo/square 💖📋 nowhere💔";
+        let got = run_with(&[("geometry", GEOMETRY)], input);
+        assert_eq!(got[1], Ok(ast::ast::NylispExpression::Number(9.0)));
+        assert_eq!(got[2], Ok(ast::ast::NylispExpression::Number(4.0)));
+        assert_eq!(got[3].as_ref().unwrap().to_string(), "💖area perimeter 💔");
+        // 🔑as alone doesn't bring in bare names, and helpers aren't reachable qualified either
+        assert!(got[4].is_err());
+        assert!(got[5].is_err());
+        assert_eq!(got[6].as_ref().unwrap_err().to_string(), "📋 no module nowhere is loaded");
+    }
+
+    // probing qualified names that don't exist doesn't grow the symbol table
+    #[test]
+    fn qualified_lookup_does_not_intern() {
+        let env = crate::environment::environment::builtin_env();
+        assert_eq!(ast::ast::get("nowhere/never-bound-export", &env), None);
+        assert!(ast::ast::Symbol::lookup("never-bound-export").is_none());
+        assert!(ast::ast::Symbol::lookup("nowhere/never-bound-export").is_none());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::ast;
 use crate::tokenizer;
 use std::rc::Rc;
+use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub struct Parser {}
@@ -44,11 +45,17 @@ impl Parser {
             tokenizer::tokenizer::QUOTE => {
                 self.parse_quote(rest_tokens)
             }
+            tokenizer::tokenizer::QUASIQUOTE | tokenizer::tokenizer::UNQUOTE | tokenizer::tokenizer::UNQUOTE_SPLICING => {
+                self.parse_quasi(cur_token, rest_tokens)
+            }
             tokenizer::tokenizer::RPAREN => {
                 Err(ast::ast::NylispError::Because("unexpected ')'".to_string()))
             }
+            tokenizer::tokenizer::DOT => {
+                Err(ast::ast::NylispError::Because(format!("unexpected {} outside of a list", tokenizer::tokenizer::DOT)))
+            }
             _ => {
-                Ok((self.parse_atom(cur_token), rest_tokens))
+                Ok((self.parse_atom(cur_token)?, rest_tokens))
             }
         }
     }
@@ -62,6 +69,9 @@ impl Parser {
             if _cur_token.as_str() == tokenizer::tokenizer::RPAREN {
                 return Ok((ast::ast::NylispExpression::List(list_obj), _rest_tokens));
             }
+            if _cur_token.as_str() == tokenizer::tokenizer::DOT {
+                return self.parse_dotted_tail(list_obj, _rest_tokens);
+            }
 
             let (cur_expr, rest_tokens) = self.parse_program(watching_tokens)?;
             list_obj.push(cur_expr);
@@ -69,24 +79,91 @@ impl Parser {
         }
     }
 
-    fn parse_atom(self, token: String) -> ast::ast::NylispExpression {
+    // 💖a b 🔗 c💔 builds pairs onto the tail, 💖a 🔗 💖b💔💔 is just the list 💖a b💔
+    fn parse_dotted_tail(self, list_obj: Vec<ast::ast::NylispExpression>, tokens: Vec<String>) -> Result<(ast::ast::NylispExpression, Vec<String>), ast::ast::NylispError> {
+        if list_obj.is_empty() {
+            return Err(ast::ast::NylispError::Because(format!("{} requires at least one element before it", tokenizer::tokenizer::DOT)));
+        }
+        if tokens.is_empty() {
+            return Err(ast::ast::NylispError::Because(format!("{} requires an element after it", tokenizer::tokenizer::DOT)));
+        }
+        let (tail, rest_tokens) = self.parse_program(tokens)?;
+        if rest_tokens.first().map(|t| t.as_str()) != Some(tokenizer::tokenizer::RPAREN) {
+            return Err(ast::ast::NylispError::Because(format!("{} requires exactly one element after it", tokenizer::tokenizer::DOT)));
+        }
+
+        let mut pair = tail;
+        for exp in list_obj.into_iter().rev() {
+            pair = ast::ast::NylispExpression::Pair(Rc::new(exp), Rc::new(pair));
+        }
+        if let Some(items) = pair.proper_list() {
+            pair = ast::ast::NylispExpression::List(items);
+        }
+        Ok((pair, rest_tokens[1..].to_vec()))
+    }
+
+    fn parse_atom(self, token: String) -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
         let is_number: bool = token.parse::<f64>().is_ok();
         if is_number {
-            return ast::ast::NylispExpression::Number(token.parse::<f64>().unwrap());
+            return Ok(ast::ast::NylispExpression::Number(token.parse::<f64>().unwrap()));
         }
 
         let is_boolearn: bool = token.as_str() == tokenizer::tokenizer::TRUE || token.as_str() == tokenizer::tokenizer::FALSE;
         if is_boolearn {
-            return ast::ast::NylispExpression::Boolean(token.as_str() == tokenizer::tokenizer::TRUE);
+            return Ok(ast::ast::NylispExpression::Boolean(token.as_str() == tokenizer::tokenizer::TRUE));
+        }
+
+        if token.as_str() == tokenizer::tokenizer::NIL {
+            return Ok(ast::ast::NylispExpression::Nil);
+        }
+
+        if let Some(text) = token.strip_prefix(tokenizer::tokenizer::STRING) {
+            return match text.strip_suffix(tokenizer::tokenizer::STRING) {
+                Some(text) => Ok(ast::ast::NylispExpression::String(text.to_string())),
+                None => Err(ast::ast::NylispError::Because(format!("unterminated string, {} is missing its closing {}", token, tokenizer::tokenizer::STRING))),
+            };
+        }
+
+        if let Some(c) = token.strip_prefix(tokenizer::tokenizer::CHAR) {
+            if !c.is_empty() {
+                return self.parse_char(c);
+            }
+        }
+
+        if let Some(key) = token.strip_prefix(tokenizer::tokenizer::KEYWORD) {
+            if !key.is_empty() {
+                return Ok(ast::ast::NylispExpression::Keyword(key.into()));
+            }
+        }
+
+        Ok(ast::ast::NylispExpression::Symbol(token.into()))
+    }
+
+    // 🔤x is the character x, 🔤space and friends name the ones that can't be written directly
+    fn parse_char(self, c: &str) -> Result<ast::ast::NylispExpression, ast::ast::NylispError> {
+        if let Some((_, ch)) = tokenizer::tokenizer::CHAR_NAMES.iter().find(|(name, _)| *name == c) {
+            return Ok(ast::ast::NylispExpression::Char(ch.to_string()));
+        }
+        if c.graphemes(true).count() != 1 {
+            return Err(ast::ast::NylispError::Because(format!("{} requires a single character, got {}", tokenizer::tokenizer::CHAR, c)));
         }
 
-        ast::ast::NylispExpression::Symbol(token)
+        Ok(ast::ast::NylispExpression::Char(c.to_string()))
     }
 
     fn parse_quote(self, tokens: Vec<String>) -> Result<(ast::ast::NylispExpression, Vec<String>), ast::ast::NylispError> {
         let (cur_expr, parsed_rest_tokens) = self.parse_program(tokens)?;
         Ok((ast::ast::NylispExpression::Quote(Rc::new(cur_expr)), parsed_rest_tokens))
     }
+
+    // 🥱x, 😳x and 🤯x read as the lists 💖🥱 x💔, 💖😳 x💔 and 💖🤯 x💔 so macros can build them too
+    fn parse_quasi(self, mark: String, tokens: Vec<String>) -> Result<(ast::ast::NylispExpression, Vec<String>), ast::ast::NylispError> {
+        if tokens.is_empty() {
+            return Err(ast::ast::NylispError::Because(format!("{} requires an element after it", mark)));
+        }
+        let (cur_expr, parsed_rest_tokens) = self.parse_program(tokens)?;
+        Ok((ast::ast::NylispExpression::List(vec![ast::ast::NylispExpression::Symbol(mark.into()), cur_expr]), parsed_rest_tokens))
+    }
 }
 
 #[cfg(test)]
@@ -125,7 +202,7 @@ mod tests {
     fn parser_nylisp_test3() {
         let input = "💖+ 1 2💔";
         let expected = ast::ast::NylispExpression::List(vec![
-            ast::ast::NylispExpression::Symbol("+".to_string()),
+            ast::ast::NylispExpression::Symbol("+".into()),
             ast::ast::NylispExpression::Number(1.0),
             ast::ast::NylispExpression::Number(2.0),
         ]);
@@ -141,10 +218,10 @@ mod tests {
     fn parser_nylisp_test4() {
         let input = "💖+ 1 💖+ 2 3💔💔";
         let expected = ast::ast::NylispExpression::List(vec![
-            ast::ast::NylispExpression::Symbol("+".to_string()),
+            ast::ast::NylispExpression::Symbol("+".into()),
             ast::ast::NylispExpression::Number(1.0),
             ast::ast::NylispExpression::List(vec![
-                ast::ast::NylispExpression::Symbol("+".to_string()),
+                ast::ast::NylispExpression::Symbol("+".into()),
                 ast::ast::NylispExpression::Number(2.0),
                 ast::ast::NylispExpression::Number(3.0),
             ]),
@@ -161,7 +238,7 @@ mod tests {
     fn parser_nylisp_quote() {
         let input = "💖☁️😪💖1 2 3💔💔";
         let expected = ast::ast::NylispExpression::List(vec![
-            ast::ast::NylispExpression::Symbol("☁️".to_string()),
+            ast::ast::NylispExpression::Symbol("☁️".into()),
             ast::ast::NylispExpression::Quote(Rc::new(ast::ast::NylispExpression::List(vec![
                 ast::ast::NylispExpression::Number(1.0),
                 ast::ast::NylispExpression::Number(2.0),
@@ -189,4 +266,121 @@ mod tests {
         let res = parser.parse_programs(tokens);
         assert_eq!(res.len(), 2);
     }
+
+    #[test]
+    fn parser_dotted_pair() {
+        let input = "💖1 2 🔗 3💔";
+        let expected = ast::ast::NylispExpression::Pair(
+            Rc::new(ast::ast::NylispExpression::Number(1.0)),
+            Rc::new(ast::ast::NylispExpression::Pair(
+                Rc::new(ast::ast::NylispExpression::Number(2.0)),
+                Rc::new(ast::ast::NylispExpression::Number(3.0)),
+            )),
+        );
+
+        let tokenizer_obj = tokenizer::tokenizer::Tokenizer::new(input.to_string());
+        let tokens = tokenizer_obj.tokenize();
+        let parser = Parser::new();
+        let (result, _) = parser.parse_program(tokens).unwrap();
+        assert_eq!(result, expected);
+        assert_eq!(format!("{}", result), "💖1 2 🔗 3 💔");
+    }
+
+    #[test]
+    fn parser_dotted_list_tail() {
+        let input = "💖1 🔗 💖2 3💔💔";
+        let expected = ast::ast::NylispExpression::List(vec![
+            ast::ast::NylispExpression::Number(1.0),
+            ast::ast::NylispExpression::Number(2.0),
+            ast::ast::NylispExpression::Number(3.0),
+        ]);
+
+        let tokenizer_obj = tokenizer::tokenizer::Tokenizer::new(input.to_string());
+        let tokens = tokenizer_obj.tokenize();
+        let parser = Parser::new();
+        let (result, _) = parser.parse_program(tokens).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parser_char() {
+        let input = "💖🔤a 🔤👍🏽 🔤space💔";
+        let expected = ast::ast::NylispExpression::List(vec![
+            ast::ast::NylispExpression::Char("a".to_string()),
+            ast::ast::NylispExpression::Char("👍🏽".to_string()),
+            ast::ast::NylispExpression::Char(" ".to_string()),
+        ]);
+
+        let tokenizer_obj = tokenizer::tokenizer::Tokenizer::new(input.to_string());
+        let tokens = tokenizer_obj.tokenize();
+        let parser = Parser::new();
+        let (result, _) = parser.parse_program(tokens).unwrap();
+        assert_eq!(result, expected);
+        assert_eq!(format!("{}", result), "💖🔤a 🔤👍🏽 🔤space 💔");
+
+        let tokens = tokenizer::tokenizer::Tokenizer::new("🔤ab".to_string()).tokenize();
+        assert!(parser.parse_program(tokens).is_err());
+    }
+
+    #[test]
+    fn parser_keyword() {
+        let input = "💖🔑name name💔";
+        let expected = ast::ast::NylispExpression::List(vec![
+            ast::ast::NylispExpression::Keyword("name".into()),
+            ast::ast::NylispExpression::Symbol("name".into()),
+        ]);
+
+        let tokenizer_obj = tokenizer::tokenizer::Tokenizer::new(input.to_string());
+        let tokens = tokenizer_obj.tokenize();
+        let parser = Parser::new();
+        let (result, _) = parser.parse_program(tokens).unwrap();
+        assert_eq!(result, expected);
+        assert_ne!(
+            ast::ast::NylispExpression::Keyword("name".into()),
+            ast::ast::NylispExpression::Symbol("name".into())
+        );
+        assert_eq!(format!("{}", result), "💖🔑name name 💔");
+    }
+
+    #[test]
+    fn parser_string() {
+        let input = "💖say 💬hi there💬💔";
+        let expected = ast::ast::NylispExpression::List(vec![
+            ast::ast::NylispExpression::Symbol("say".into()),
+            ast::ast::NylispExpression::String("hi there".to_string()),
+        ]);
+
+        let tokenizer_obj = tokenizer::tokenizer::Tokenizer::new(input.to_string());
+        let tokens = tokenizer_obj.tokenize();
+        let parser = Parser::new();
+        let (result, _) = parser.parse_program(tokens).unwrap();
+        assert_eq!(result, expected);
+
+        let tokens = tokenizer::tokenizer::Tokenizer::new("💬💬".to_string()).tokenize();
+        assert_eq!(parser.parse_program(tokens).unwrap().0, ast::ast::NylispExpression::String(String::new()));
+
+        let tokens = tokenizer::tokenizer::Tokenizer::new("💬oops".to_string()).tokenize();
+        assert!(parser.parse_program(tokens).is_err());
+    }
+
+    #[test]
+    fn parser_nylisp_quasiquote() {
+        let input = "🥱💖a 😳b💔";
+        let expected = ast::ast::NylispExpression::List(vec![
+            ast::ast::NylispExpression::Symbol("🥱".into()),
+            ast::ast::NylispExpression::List(vec![
+                ast::ast::NylispExpression::Symbol("a".into()),
+                ast::ast::NylispExpression::List(vec![
+                    ast::ast::NylispExpression::Symbol("😳".into()),
+                    ast::ast::NylispExpression::Symbol("b".into()),
+                ]),
+            ]),
+        ]);
+
+        let tokenizer_obj = tokenizer::tokenizer::Tokenizer::new(input.to_string());
+        let tokens = tokenizer_obj.tokenize();
+        let parser = Parser::new();
+        let (result, _) = parser.parse_program(tokens).unwrap();
+        assert_eq!(result, expected);
+    }
 }
@@ -1,12 +1,72 @@
 pub const LPAREN: &str = "💖";
 pub const RPAREN: &str = "💔";
 pub const QUOTE: &str = "😪";
+pub const QUASIQUOTE: &str = "🥱";
+pub const UNQUOTE: &str = "😳";
+pub const UNQUOTE_SPLICING: &str = "🤯";
+pub const DOT: &str = "🔗";
 pub const TRUE: &str = "👍";
 pub const FALSE: &str = "👎";
+pub const NIL: &str = "🫥";
+pub const CHAR: &str = "🔤";
+pub const KEYWORD: &str = "🔑";
+// string literals are written 💬like this💬, the tokenizer keeps everything between the marks as one token
+pub const STRING: &str = "💬";
+// characters that can't follow 🔤 directly, because they are whitespace or get split off by the tokenizer
+pub const CHAR_NAMES: [(&str, &str); 10] = [
+    ("space", " "),
+    ("newline", "\n"),
+    ("tab", "\t"),
+    ("heart", LPAREN),
+    ("broken-heart", RPAREN),
+    ("sleepy", QUOTE),
+    ("yawning", QUASIQUOTE),
+    ("flushed", UNQUOTE),
+    ("exploding-head", UNQUOTE_SPLICING),
+    ("link", DOT),
+];
 pub const IF: &str = "🐶";
 pub const VAR: &str = "🌹";
+pub const SET: &str = "📝";
 pub const CLOSURE: &str = "🐷";
+pub const DEFUN: &str = "🍳";
 pub const SCOPED_LET: &str = "🍙";
+pub const OPTIONAL: &str = "❓";
+pub const KEYS: &str = "🔐";
+pub const SEQUENTIAL_LET: &str = "🍢";
+pub const RECURSIVE_LET: &str = "🍥";
+pub const NAMED_LET: &str = "🔁";
+pub const BEGIN: &str = "👣";
+pub const COND: &str = "🚦";
+pub const CASE: &str = "🔀";
+pub const ELSE: &str = "🤷";
+pub const AND: &str = "😎";
+pub const OR: &str = "😕";
+pub const RAISE: &str = "🧨";
+pub const TRY: &str = "🥅";
+pub const CATCH: &str = "🎣";
+pub const FINALLY: &str = "🧹";
+pub const WHEN: &str = "✅";
+pub const UNLESS: &str = "⛔";
+pub const CALLCC: &str = "🔖";
+pub const RESET: &str = "🚧";
+pub const SHIFT: &str = "🪝";
+pub const DEFMACRO: &str = "🧙";
+pub const MACROEXPAND: &str = "🔍";
+pub const DEFINE_SYNTAX: &str = "🧬";
+pub const ELLIPSIS: &str = "🔂";
+pub const MATCH: &str = "🧩";
+pub const RECORD: &str = "📇";
+pub const DELAY: &str = "💤";
+pub const FORCE: &str = "⏰";
+pub const STREAM_CONS: &str = "🌊";
+pub const RESUME: &str = "🎬";
+pub const YIELD: &str = "🫳";
+pub const IMPORT: &str = "📥";
+pub const EXPORT: &str = "📤";
+pub const EXPORTS: &str = "📋";
+pub const CONTRACT: &str = "📐";
+pub const CONTRACTS: &str = "🦺";
 
 
 pub struct Tokenizer {
@@ -19,13 +79,27 @@ impl Tokenizer {
     }
 
     pub fn tokenize(&self) -> Vec<String> {
-        let paren_spaced = self.input
-            .replace(LPAREN.chars().collect::<Vec<char>>()[0], format!(" {} ", LPAREN).as_str())
-            .replace(RPAREN.chars().collect::<Vec<char>>()[0], format!(" {} ", RPAREN).as_str())
-            .replace(QUOTE.chars().collect::<Vec<char>>()[0], format!(" {} ", QUOTE).as_str());
-        paren_spaced.split_whitespace()
-            .map(|x| x.to_string())
-            .collect()
+        let mut tokens: Vec<String> = Vec::new();
+        let segments: Vec<&str> = self.input.split(STRING).collect();
+        for (i, segment) in segments.iter().enumerate() {
+            // text between 💬 marks stays one token, spaces and hearts included
+            if i % 2 == 1 {
+                let closed = i + 1 < segments.len();
+                tokens.push(format!("{}{}{}", STRING, segment, if closed { STRING } else { "" }));
+                continue;
+            }
+            let paren_spaced = segment
+                .replace(LPAREN.chars().collect::<Vec<char>>()[0], format!(" {} ", LPAREN).as_str())
+                .replace(RPAREN.chars().collect::<Vec<char>>()[0], format!(" {} ", RPAREN).as_str())
+                .replace(QUOTE.chars().collect::<Vec<char>>()[0], format!(" {} ", QUOTE).as_str())
+                .replace(QUASIQUOTE.chars().collect::<Vec<char>>()[0], format!(" {} ", QUASIQUOTE).as_str())
+                .replace(UNQUOTE.chars().collect::<Vec<char>>()[0], format!(" {} ", UNQUOTE).as_str())
+                .replace(UNQUOTE_SPLICING.chars().collect::<Vec<char>>()[0], format!(" {} ", UNQUOTE_SPLICING).as_str())
+                .replace(DOT.chars().collect::<Vec<char>>()[0], format!(" {} ", DOT).as_str());
+            tokens.extend(paren_spaced.split_whitespace().map(|x| x.to_string()));
+        }
+
+        tokens
     }
 }
 
@@ -50,4 +124,41 @@ mod tests {
         let tokens = tokenizer.tokenize();
         assert_eq!(tokens, expected);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn tokenize_nylisp_dotted_pair() {
+        let input = "💖1🔗2💔";
+        let expected = vec!["💖", "1", "🔗", "2", "💔"];
+        let tokenizer = Tokenizer::new(input.to_string());
+        let tokens = tokenizer.tokenize();
+        assert_eq!(tokens, expected);
+    }
+
+    #[test]
+    fn tokenize_nylisp_string() {
+        let input = "💖say 💬hello 💖world💔💬 💬💬 💬open";
+        let expected = vec!["💖", "say", "💬hello 💖world💔💬", "💬💬", "💬open"];
+        let tokenizer = Tokenizer::new(input.to_string());
+        let tokens = tokenizer.tokenize();
+        assert_eq!(tokens, expected);
+    }
+
+    #[test]
+    fn tokenize_nylisp_string_next_to_syntax() {
+        // quotes, dots and hearts right against a string are split off, inside it they stay as they are
+        let input = "😪💬a😪b🔗c💬💖💬 x 💬💔";
+        let expected = vec!["😪", "💬a😪b🔗c💬", "💖", "💬 x 💬", "💔"];
+        let tokenizer = Tokenizer::new(input.to_string());
+        let tokens = tokenizer.tokenize();
+        assert_eq!(tokens, expected);
+    }
+
+    #[test]
+    fn tokenize_nylisp_quasiquote() {
+        let input = "🥱💖a 😳b 🤯c💔";
+        let expected = vec!["🥱", "💖", "a", "😳", "b", "🤯", "c", "💔"];
+        let tokenizer = Tokenizer::new(input.to_string());
+        let tokens = tokenizer.tokenize();
+        assert_eq!(tokens, expected);
+    }
+}
@@ -1,9 +1,26 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 pub use nylisp_eval;
 
 #[wasm_bindgen]
 pub struct NyLisp {
-    global_env: nylisp_eval::ast::ast::Environment<'static>
+    global_env: nylisp_eval::ast::ast::Environment,
+    // module sources js handed over, 📥 looks names up here through the loader installed in new
+    modules: Rc<RefCell<HashMap<String, String>>>
+}
+
+// reads from the same map add_module writes to, so one loader serves every module js hands over
+struct SharedLoader(Rc<RefCell<HashMap<String, String>>>);
+
+impl nylisp_eval::module::module::ModuleLoader for SharedLoader {
+    fn load(&self, name: &str) -> Result<String, nylisp_eval::ast::ast::NylispError> {
+        match self.0.borrow().get(name) {
+            Some(source) => Ok(source.clone()),
+            None => Err(nylisp_eval::ast::ast::NylispError::of_kind("module", format!("no module named {}", name)))
+        }
+    }
 }
 
 // excute programs
@@ -11,9 +28,15 @@ pub struct NyLisp {
 impl NyLisp {
     #[wasm_bindgen(constructor)]
     pub fn new() -> NyLisp {
-        NyLisp {
-            global_env: nylisp_eval::environment::environment::builtin_env()
-        }
+        let global_env = nylisp_eval::environment::environment::builtin_env();
+        let modules = Rc::new(RefCell::new(HashMap::new()));
+        global_env.set_module_loader(Rc::new(SharedLoader(modules.clone())));
+        NyLisp { global_env, modules }
+    }
+
+    // make source importable as name, replacing an earlier module of the same name that hasn't been imported yet
+    pub fn add_module(&mut self, name: String, source: String) {
+        self.modules.borrow_mut().insert(name, source);
     }
 
     pub fn run(&mut self, _lines: String) -> Vec<JsValue> {
@@ -30,6 +53,15 @@ impl NyLisp {
 
         _evaluator_validator(result)
     }
+
+    // resume the coroutine bound to name, the host keeps pulling values until status says it is dead
+    pub fn pull(&mut self, name: String) -> Vec<JsValue> {
+        self.run(format!("💖🎬 {}💔", name))
+    }
+
+    pub fn status(&mut self, name: String) -> Vec<JsValue> {
+        self.run(format!("💖🚥 {}💔", name))
+    }
 }
 
 fn _parser_validator(ast: Vec<Result<nylisp_eval::ast::ast::NylispExpression, nylisp_eval::ast::ast::NylispError>>) -> Result<Vec<nylisp_eval::ast::ast::NylispExpression>, JsValue> {
@@ -8,4 +8,12 @@ fn test_run() {
     let input = "💖🍙 💖💖x 2💔💖y 2💔💔 💖+ x y💔💔".to_string();
     let result = nylisp.run(input);
     assert_eq!(result[0].as_string().unwrap(), "4");
-}
\ No newline at end of file
+}
+#[wasm_bindgen_test]
+fn test_add_module() {
+    let mut nylisp = NyLisp::new();
+    nylisp.add_module("double".to_string(), "💖📤 double💔 💖🍳 double 💖x💔 💖* 2 x💔💔".to_string());
+    nylisp.add_module("triple".to_string(), "💖📤 triple💔 💖🍳 triple 💖x💔 💖* 3 x💔💔".to_string());
+    let result = nylisp.run("💖📥 double💔 💖📥 triple💔 💖+ 💖double 1💔 💖triple 1💔💔".to_string());
+    assert_eq!(result[2].as_string().unwrap(), "5");
+}